@@ -388,6 +388,11 @@ fn parse_args() -> Result<Args, String> {
         text_rendering: args.text_rendering,
         image_rendering: args.image_rendering,
         keep_named_groups,
+        size_fallback: usvg::SizeFallback::default(),
+        viewport_size: None,
+        load_external_stylesheets: true,
+        load_external_use: true,
+        user_stylesheet: None,
         fontdb,
     };
 
@@ -34,7 +34,7 @@ pub fn mask(
         crate::render::render_group(node, &mut RenderState::Ok, layers, &mask_cr);
     }
 
-    {
+    if mask.kind == usvg::MaskType::Luminance {
         let mut data = try_opt_warn!(
             mask_surface.get_data().ok(),
             "Failed to borrow a surface for mask '{}'.", mask.id
@@ -64,7 +64,7 @@ pub fn mask(
     cr.reset_source_rgba();
 }
 
-/// Converts an image into an alpha mask.
+/// Converts an image into an alpha mask using its luminance.
 fn image_to_mask(
     data: &mut [rgb::alt::BGRA8],
     img_size: ScreenSize,
@@ -28,6 +28,12 @@ pub fn fill(
                             usvg::NodeKind::Pattern(ref pattern) => {
                                 prepare_pattern(&node, pattern, fill.opacity, bbox, cr);
                             }
+                            usvg::NodeKind::MeshGradient(ref mesh) => {
+                                prepare_mesh(mesh, fill.opacity, bbox, 0.0, cr);
+                            }
+                            usvg::NodeKind::Hatch(ref hatch) => {
+                                prepare_hatch(tree, hatch, fill.opacity, bbox, cr);
+                            }
                             _ => {}
                         }
                     }
@@ -70,6 +76,14 @@ pub fn stroke(
                             usvg::NodeKind::Pattern(ref pattern) => {
                                 prepare_pattern(&node, pattern, stroke.opacity, bbox, cr);
                             }
+                            usvg::NodeKind::MeshGradient(ref mesh) => {
+                                // A stroke can be painted outside the bbox.
+                                let margin = stroke.width.value() * stroke.miterlimit.value();
+                                prepare_mesh(mesh, stroke.opacity, bbox, margin, cr);
+                            }
+                            usvg::NodeKind::Hatch(ref hatch) => {
+                                prepare_hatch(tree, hatch, stroke.opacity, bbox, cr);
+                            }
                             _ => {}
                         }
                     }
@@ -155,7 +169,7 @@ fn prepare_base_gradient(
     matrix.invert();
     grad.set_matrix(matrix);
 
-    for stop in &g.stops {
+    for stop in g.srgb_stops().iter() {
         grad.add_color_stop_rgba(
             stop.offset.value(),
             stop.color.red as f64 / 255.0,
@@ -207,7 +221,49 @@ fn prepare_pattern(
     ts.translate(r.x(), r.y());
     ts.scale(1.0 / sx, 1.0 / sy);
 
+    set_pattern_source(surface, img_size, ts, opacity, cr);
+}
+
+fn prepare_hatch(
+    tree: &usvg::Tree,
+    hatch: &usvg::Hatch,
+    opacity: usvg::Opacity,
+    bbox: Rect,
+    cr: &cairo::Context,
+) {
+    let tile = try_opt!(hatch.tile(bbox));
+    let r = tile.rect;
+
+    let global_ts = usvg::Transform::from_native(&cr.get_matrix());
+    let (sx, sy) = global_ts.get_scale();
+
+    let img_size = try_opt!(Size::new(r.width() * sx, r.height() * sy)).to_screen_size();
+    let surface = try_opt!(crate::render::create_subsurface(img_size));
+
+    {
+        let sub_cr = cairo::Context::new(&surface);
+        let scale_ts = cairo::Matrix::new(sx, 0.0, 0.0, sy, 0.0, 0.0);
+        for path in &tile.paths {
+            sub_cr.set_matrix(scale_ts);
+            sub_cr.transform(path.transform.to_native());
+            crate::path::draw(tree, path, &sub_cr);
+        }
+    }
+
+    let mut ts = tile.transform;
+    ts.translate(r.x(), r.y());
+    ts.scale(1.0 / sx, 1.0 / sy);
+
+    set_pattern_source(surface, img_size, ts, opacity, cr);
+}
 
+fn set_pattern_source(
+    surface: cairo::ImageSurface,
+    img_size: ScreenSize,
+    ts: usvg::Transform,
+    opacity: usvg::Opacity,
+    cr: &cairo::Context,
+) {
     let surface = if !opacity.is_default() {
         // If `opacity` isn't `1` then we have to make image semitransparent.
         // The only way to do this is by making a new image and rendering
@@ -234,3 +290,46 @@ fn prepare_pattern(
 
     cr.set_source(&patt);
 }
+
+fn prepare_mesh(
+    mesh: &usvg::MeshGradient,
+    opacity: usvg::Opacity,
+    bbox: Rect,
+    margin: f64,
+    cr: &cairo::Context,
+) {
+    let global_ts = usvg::Transform::from_native(&cr.get_matrix());
+
+    // Clip extents in the device space.
+    cr.save();
+    cr.identity_matrix();
+    let (_, _, right, bottom) = cr.clip_extents();
+    cr.restore();
+
+    let canvas_size = try_opt!(ScreenSize::new(right.ceil() as u32, bottom.ceil() as u32));
+    let img = try_opt!(mesh.rasterize(bbox, margin, global_ts, canvas_size, opacity));
+
+    let img_ts = img.transform;
+    let img = crate::image::Image {
+        data: crate::image::ImageData::RGBA(img.data),
+        size: img.size,
+    };
+
+    let mut surface = try_opt!(crate::render::create_subsurface(img.size));
+    {
+        // Unwrap is safe, because no one uses the surface.
+        let mut surface_data = surface.get_data().unwrap();
+        crate::image::image_to_surface(&img, &mut surface_data);
+    }
+
+    // The image covers the whole painted area, so its repeating will not be visible.
+    let patt = cairo::SurfacePattern::create(&surface);
+    patt.set_extend(cairo::Extend::Repeat);
+    patt.set_filter(cairo::Filter::Best);
+
+    let mut m: cairo::Matrix = img_ts.to_native();
+    m.invert();
+    patt.set_matrix(m);
+
+    cr.set_source(&patt);
+}
@@ -30,14 +30,28 @@ pub fn draw(
         cr.set_antialias(cairo::Antialias::None);
     }
 
-    crate::paint_server::fill(tree, &path.fill, style_bbox, cr);
-    if path.stroke.is_some() {
-        cr.fill_preserve();
+    let stroke = path.stroke_with_transform(usvg::Transform::from_native(&cr.get_matrix()));
 
-        crate::paint_server::stroke(tree, &path.stroke, style_bbox, cr);
-        cr.stroke();
+    if path.paint_order.is_stroke_first() {
+        crate::paint_server::stroke(tree, &stroke, style_bbox, cr);
+        if path.fill.is_some() {
+            cr.stroke_preserve();
+
+            crate::paint_server::fill(tree, &path.fill, style_bbox, cr);
+            cr.fill();
+        } else {
+            cr.stroke();
+        }
     } else {
-        cr.fill();
+        crate::paint_server::fill(tree, &path.fill, style_bbox, cr);
+        if stroke.is_some() {
+            cr.fill_preserve();
+
+            crate::paint_server::stroke(tree, &stroke, style_bbox, cr);
+            cr.stroke();
+        } else {
+            cr.fill();
+        }
     }
 
     // Revert anti-aliasing.
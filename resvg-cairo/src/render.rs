@@ -231,7 +231,9 @@ fn render_group_impl(
 
     // Filter can be rendered on an object without a bbox,
     // as long as filter uses `userSpaceOnUse`.
-    if let Some(ref id) = g.filter {
+    //
+    // Each filter is applied to the result of the previous one.
+    for id in &g.filters {
         if let Some(filter_node) = node.tree().defs_by_id(id) {
             if let usvg::NodeKind::Filter(ref filter) = *filter_node.borrow() {
                 let ts = usvg::Transform::from_native(&curr_ts);
@@ -273,12 +275,14 @@ fn render_group_impl(
     let curr_matrix = cr.get_matrix();
     cr.set_matrix(cairo::Matrix::identity());
     cr.set_source_surface(&*sub_surface, 0.0, 0.0);
+    cr.set_operator(convert_blend_mode(g.blend_mode));
     if !g.opacity.is_default() {
         cr.paint_with_alpha(g.opacity.value());
     } else {
         cr.paint();
     }
 
+    cr.set_operator(cairo::Operator::Over);
     cr.set_matrix(curr_matrix);
 
     // All layers must be unlinked from the main context/cr after used.
@@ -288,6 +292,27 @@ fn render_group_impl(
     bbox
 }
 
+fn convert_blend_mode(mode: usvg::BlendMode) -> cairo::Operator {
+    match mode {
+        usvg::BlendMode::Normal => cairo::Operator::Over,
+        usvg::BlendMode::Multiply => cairo::Operator::Multiply,
+        usvg::BlendMode::Screen => cairo::Operator::Screen,
+        usvg::BlendMode::Overlay => cairo::Operator::Overlay,
+        usvg::BlendMode::Darken => cairo::Operator::Darken,
+        usvg::BlendMode::Lighten => cairo::Operator::Lighten,
+        usvg::BlendMode::ColorDodge => cairo::Operator::ColorDodge,
+        usvg::BlendMode::ColorBurn => cairo::Operator::ColorBurn,
+        usvg::BlendMode::HardLight => cairo::Operator::HardLight,
+        usvg::BlendMode::SoftLight => cairo::Operator::SoftLight,
+        usvg::BlendMode::Difference => cairo::Operator::Difference,
+        usvg::BlendMode::Exclusion => cairo::Operator::Exclusion,
+        usvg::BlendMode::Hue => cairo::Operator::HslHue,
+        usvg::BlendMode::Saturation => cairo::Operator::HslSaturation,
+        usvg::BlendMode::Color => cairo::Operator::HslColor,
+        usvg::BlendMode::Luminosity => cairo::Operator::HslLuminosity,
+    }
+}
+
 /// Renders an image used by `BackgroundImage` or `BackgroundAlpha` filter inputs.
 fn prepare_filter_background(
     parent: &usvg::Node,
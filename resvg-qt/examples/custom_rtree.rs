@@ -22,6 +22,7 @@ fn main() {
             units: usvg::Units::ObjectBoundingBox,
             transform: usvg::Transform::default(),
             spread_method: usvg::SpreadMethod::Pad,
+            color_interpolation: usvg::ColorInterpolation::SRGB,
             stops: vec![
                 usvg::Stop {
                     offset: usvg::StopOffset::new(0.0),
@@ -212,6 +212,16 @@ qtc_qtransform *qtc_qpainter_get_transform(qtc_qpainter *c_p)
     return reinterpret_cast<qtc_qtransform*>(new QTransform(ts));
 }
 
+uint32_t qtc_qpainter_get_device_width(qtc_qpainter *c_p)
+{
+    return PAINTER_CAST->device()->width();
+}
+
+uint32_t qtc_qpainter_get_device_height(qtc_qpainter *c_p)
+{
+    return PAINTER_CAST->device()->height();
+}
+
 void qtc_qpainter_set_transform(qtc_qpainter *c_p, qtc_qtransform *c_ts, bool combine)
 {
     PAINTER_CAST->setTransform(*TRANSFORM_CAST, combine);
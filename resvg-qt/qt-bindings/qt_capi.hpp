@@ -127,6 +127,8 @@ void qtc_qpainter_draw_rect(qtc_qpainter *c_p, double x, double y, double w, dou
 void qtc_qpainter_translate(qtc_qpainter *c_p, double tx, double ty);
 void qtc_qpainter_scale(qtc_qpainter *c_p, double sx, double sy);
 qtc_qtransform* qtc_qpainter_get_transform(qtc_qpainter *c_p);
+uint32_t qtc_qpainter_get_device_width(qtc_qpainter *c_p);
+uint32_t qtc_qpainter_get_device_height(qtc_qpainter *c_p);
 void qtc_qpainter_set_transform(qtc_qpainter *c_p, qtc_qtransform *q_ts, bool combine);
 void qtc_qpainter_set_clip_rect(qtc_qpainter *c_p, double x, double y, double w, double h);
 void qtc_qpainter_reset_clip_path(qtc_qpainter *c_p);
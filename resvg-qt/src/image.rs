@@ -30,7 +30,7 @@ pub fn draw_raster(
     rendering_mode: usvg::ImageRendering,
     p: &mut qt::Painter,
 ) {
-    let img = match qt::Image::from_data(data) {
+    let mut img = match qt::Image::from_data(data) {
         Some(img) => img,
         None => {
             log::warn!("Failed to load an embedded image.");
@@ -38,6 +38,14 @@ pub fn draw_raster(
         }
     };
 
+    let profile = usvg::ColorProfile::from_png(data)
+        .or_else(|| usvg::ColorProfile::from_jpeg(data));
+    if let Some(profile) = profile {
+        // ARGB32 is a non-premultiplied BGRA on little-endian.
+        img = try_opt!(img.to_rgba());
+        profile.convert_bgra(&mut img.data_mut());
+    }
+
     let img_size = try_opt!(ScreenSize::new(img.width(), img.height()));
 
     if rendering_mode == usvg::ImageRendering::OptimizeSpeed {
@@ -33,8 +33,10 @@ pub fn mask(
         crate::render::render_group(node, &mut RenderState::Ok, layers, &mut mask_p);
     }
 
-    use rgb::FromSlice;
-    image_to_mask(mask_img.data_mut().as_bgra_mut(), layers.image_size());
+    if mask.kind == usvg::MaskType::Luminance {
+        use rgb::FromSlice;
+        image_to_mask(mask_img.data_mut().as_bgra_mut(), layers.image_size());
+    }
 
     if let Some(ref id) = mask.mask {
         if let Some(ref mask_node) = node.tree().defs_by_id(id) {
@@ -49,7 +51,7 @@ pub fn mask(
     p.draw_image(0.0, 0.0, &mask_img);
 }
 
-/// Converts an image into an alpha mask.
+/// Converts an image into an alpha mask using its luminance.
 fn image_to_mask(
     data: &mut [rgb::alt::BGRA8],
     img_size: ScreenSize,
@@ -33,6 +33,13 @@ pub fn fill(
                                 let ts = p.get_transform();
                                 prepare_pattern(&node, pattern, ts, bbox, opacity, &mut brush);
                             }
+                            usvg::NodeKind::MeshGradient(ref mesh) => {
+                                prepare_mesh(mesh, p, bbox, 0.0, opacity, &mut brush);
+                            }
+                            usvg::NodeKind::Hatch(ref hatch) => {
+                                let ts = p.get_transform();
+                                prepare_hatch(tree, hatch, ts, bbox, opacity, &mut brush);
+                            }
                             _ => {}
                         }
                     }
@@ -78,6 +85,15 @@ pub fn stroke(
                                 let ts = p.get_transform();
                                 prepare_pattern(&node, pattern, ts, bbox, opacity, &mut brush);
                             }
+                            usvg::NodeKind::MeshGradient(ref mesh) => {
+                                // A stroke can be painted outside the bbox.
+                                let margin = stroke.width.value() * stroke.miterlimit.value();
+                                prepare_mesh(mesh, p, bbox, margin, opacity, &mut brush);
+                            }
+                            usvg::NodeKind::Hatch(ref hatch) => {
+                                let ts = p.get_transform();
+                                prepare_hatch(tree, hatch, ts, bbox, opacity, &mut brush);
+                            }
                             _ => {}
                         }
                     }
@@ -154,7 +170,7 @@ fn prepare_base_gradient(
     };
     grad.set_spread(spread_method);
 
-    for stop in &g.stops {
+    for stop in g.srgb_stops().iter() {
         grad.set_color_at(
             stop.offset.value(),
             stop.color.red,
@@ -222,6 +238,57 @@ fn prepare_pattern(
     crate::render::render_group(pattern_node, &mut RenderState::Ok, &mut layers, &mut p);
     p.end();
 
+    let mut ts = usvg::Transform::default();
+    ts.append(&pattern.transform);
+    ts.translate(r.x(), r.y());
+    ts.scale(1.0 / sx, 1.0 / sy);
+
+    set_pattern_image(img, img_size, ts, opacity, brush);
+}
+
+fn prepare_hatch(
+    tree: &usvg::Tree,
+    hatch: &usvg::Hatch,
+    global_ts: qt::Transform,
+    bbox: Rect,
+    opacity: usvg::Opacity,
+    brush: &mut qt::Brush,
+) {
+    let tile = try_opt!(hatch.tile(bbox));
+    let r = tile.rect;
+
+    let global_ts = usvg::Transform::from_native(&global_ts);
+    let (sx, sy) = global_ts.get_scale();
+
+    let img_size = try_opt!(Size::new(r.width() * sx, r.height() * sy)).to_screen_size();
+    let mut img = try_opt!(crate::render::create_subimage(img_size));
+    img.fill(0, 0, 0, 0);
+
+    let mut p = qt::Painter::new(&mut img);
+
+    p.scale(sx, sy);
+    let scale_ts = p.get_transform();
+    for path in &tile.paths {
+        p.set_transform(&scale_ts);
+        p.apply_transform(&path.transform.to_native());
+        crate::path::draw(tree, path, &mut p);
+    }
+    p.end();
+
+    let mut ts = tile.transform;
+    ts.translate(r.x(), r.y());
+    ts.scale(1.0 / sx, 1.0 / sy);
+
+    set_pattern_image(img, img_size, ts, opacity, brush);
+}
+
+fn set_pattern_image(
+    img: qt::Image,
+    img_size: ScreenSize,
+    ts: usvg::Transform,
+    opacity: usvg::Opacity,
+    brush: &mut qt::Brush,
+) {
     let img = if !opacity.is_default() {
         // If `opacity` isn't `1` then we have to make image semitransparent.
         // The only way to do this is by making a new image and rendering
@@ -241,10 +308,35 @@ fn prepare_pattern(
     };
 
     brush.set_pattern(img);
-
-    let mut ts = usvg::Transform::default();
-    ts.append(&pattern.transform);
-    ts.translate(r.x(), r.y());
-    ts.scale(1.0 / sx, 1.0 / sy);
     brush.set_transform(ts.to_native());
 }
+
+fn prepare_mesh(
+    mesh: &usvg::MeshGradient,
+    p: &qt::Painter,
+    bbox: Rect,
+    margin: f64,
+    opacity: usvg::Opacity,
+    brush: &mut qt::Brush,
+) {
+    let global_ts = usvg::Transform::from_native(&p.get_transform());
+    let canvas_size = try_opt!(ScreenSize::new(p.device_width(), p.device_height()));
+    let img = try_opt!(mesh.rasterize(bbox, margin, global_ts, canvas_size, opacity));
+
+    let (w, h) = img.size.dimensions();
+    let mut qimg = try_opt!(qt::Image::new_rgba(w, h));
+    {
+        // ARGB32 is a non-premultiplied BGRA on little-endian.
+        let mut data = qimg.data_mut();
+        for (p, c) in data.chunks_mut(4).zip(img.data.chunks(4)) {
+            p[0] = c[2];
+            p[1] = c[1];
+            p[2] = c[0];
+            p[3] = c[3];
+        }
+    }
+
+    // The image covers the whole painted area, so its repeating will not be visible.
+    brush.set_pattern(qimg);
+    brush.set_transform(img.transform.to_native());
+}
@@ -27,11 +27,24 @@ pub fn draw(
     // so we can pass whatever rect we want, because it will not be used anyway.
     let style_bbox = bbox.unwrap_or_else(|| Rect::new(0.0, 0.0, 1.0, 1.0).unwrap());
 
-    crate::paint_server::fill(tree, &path.fill, style_bbox, p);
-    crate::paint_server::stroke(tree, &path.stroke, style_bbox, p);
     p.set_antialiasing(path.rendering_mode.use_shape_antialiasing());
 
-    p.draw_path(&new_path);
+    let stroke = path.stroke_with_transform(usvg::Transform::from_native(&p.get_transform()));
+
+    if path.paint_order.is_stroke_first() {
+        // Qt always fills a path before stroking it, so we have to draw it twice.
+        crate::paint_server::fill(tree, &None, style_bbox, p);
+        crate::paint_server::stroke(tree, &stroke, style_bbox, p);
+        p.draw_path(&new_path);
+
+        crate::paint_server::fill(tree, &path.fill, style_bbox, p);
+        crate::paint_server::stroke(tree, &None, style_bbox, p);
+        p.draw_path(&new_path);
+    } else {
+        crate::paint_server::fill(tree, &path.fill, style_bbox, p);
+        crate::paint_server::stroke(tree, &stroke, style_bbox, p);
+        p.draw_path(&new_path);
+    }
 
     // Revert anti-aliasing.
     p.set_antialiasing(true);
@@ -153,6 +153,8 @@ mod ffi {
         pub fn qtc_qpainter_translate(c_p: *mut qtc_qpainter, tx: f64, ty: f64);
         pub fn qtc_qpainter_scale(c_p: *mut qtc_qpainter, sx: f64, sy: f64);
         pub fn qtc_qpainter_get_transform(c_p: *mut qtc_qpainter) -> *mut qtc_qtransform;
+        pub fn qtc_qpainter_get_device_width(c_p: *mut qtc_qpainter) -> u32;
+        pub fn qtc_qpainter_get_device_height(c_p: *mut qtc_qpainter) -> u32;
         pub fn qtc_qpainter_set_transform(
             c_p: *mut qtc_qpainter,
             q_ts: *mut qtc_qtransform,
@@ -499,6 +501,14 @@ impl Painter {
         unsafe { Transform(ffi::qtc_qpainter_get_transform(self.0)) }
     }
 
+    pub fn device_width(&self) -> u32 {
+        unsafe { ffi::qtc_qpainter_get_device_width(self.0) }
+    }
+
+    pub fn device_height(&self) -> u32 {
+        unsafe { ffi::qtc_qpainter_get_device_height(self.0) }
+    }
+
     pub fn set_transform(&mut self, ts: &Transform) {
         unsafe { ffi::qtc_qpainter_set_transform(self.0, ts.0, false) }
     }
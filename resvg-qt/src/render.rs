@@ -193,7 +193,9 @@ fn render_group_impl(
 
     // Filter can be rendered on an object without a bbox,
     // as long as filter uses `userSpaceOnUse`.
-    if let Some(ref id) = g.filter {
+    //
+    // Each filter is applied to the result of the previous one.
+    for id in &g.filters {
         if let Some(filter_node) = node.tree().defs_by_id(id) {
             if let usvg::NodeKind::Filter(ref filter) = *filter_node.borrow() {
                 let ts = usvg::Transform::from_native(&curr_ts);
@@ -236,17 +238,45 @@ fn render_group_impl(
         p.set_opacity(g.opacity.value());
     }
 
+    p.set_composition_mode(convert_blend_mode(g.blend_mode));
+
     let curr_ts = p.get_transform();
     p.set_transform(&qt::Transform::default());
 
     p.draw_image(0.0, 0.0, &sub_img);
 
     p.set_opacity(1.0);
+    p.set_composition_mode(qt::CompositionMode::SourceOver);
     p.set_transform(&curr_ts);
 
     bbox
 }
 
+fn convert_blend_mode(mode: usvg::BlendMode) -> qt::CompositionMode {
+    match mode {
+        usvg::BlendMode::Normal => qt::CompositionMode::SourceOver,
+        usvg::BlendMode::Multiply => qt::CompositionMode::Multiply,
+        usvg::BlendMode::Screen => qt::CompositionMode::Screen,
+        usvg::BlendMode::Overlay => qt::CompositionMode::Overlay,
+        usvg::BlendMode::Darken => qt::CompositionMode::Darken,
+        usvg::BlendMode::Lighten => qt::CompositionMode::Lighten,
+        usvg::BlendMode::ColorDodge => qt::CompositionMode::ColorDodge,
+        usvg::BlendMode::ColorBurn => qt::CompositionMode::ColorBurn,
+        usvg::BlendMode::HardLight => qt::CompositionMode::HardLight,
+        usvg::BlendMode::SoftLight => qt::CompositionMode::SoftLight,
+        usvg::BlendMode::Difference => qt::CompositionMode::Difference,
+        usvg::BlendMode::Exclusion => qt::CompositionMode::Exclusion,
+        usvg::BlendMode::Hue |
+        usvg::BlendMode::Saturation |
+        usvg::BlendMode::Color |
+        usvg::BlendMode::Luminosity => {
+            // Qt doesn't support non-separable blend modes.
+            warn!("{:?} blend mode is not supported by the Qt backend.", mode);
+            qt::CompositionMode::SourceOver
+        }
+    }
+}
+
 /// Renders an image used by `BackgroundImage` or `BackgroundAlpha` filter inputs.
 fn prepare_filter_background(
     parent: &usvg::Node,
@@ -362,6 +362,10 @@ fn apply2(
 
             Ok(Image::from_image(dt, input.color_space))
         }
+        usvg::FilterKind::FeDropShadow(ref fe) => {
+            let input = get_input(&fe.input, region, inputs, &results)?;
+            apply_drop_shadow(fe, filter.primitive_units, cs, region, bbox, ts, input)
+        }
         usvg::FilterKind::FeComposite(ref fe) => {
             let input1 = get_input(&fe.input1, region, inputs, &results)?;
             let input2 = get_input(&fe.input2, region, inputs, &results)?;
@@ -832,6 +836,60 @@ fn apply_composite(
     Ok(Image::from_image(dt, cs))
 }
 
+fn apply_drop_shadow(
+    fe: &usvg::FeDropShadow,
+    units: usvg::Units,
+    cs: ColorSpace,
+    region: ScreenRect,
+    bbox: Option<Rect>,
+    ts: &usvg::Transform,
+    input: Image,
+) -> Result<Image, Error> {
+    let mut buffer = input.clone().into_color_space(cs)?.take()?;
+
+    let blur = usvg::FeGaussianBlur {
+        input: fe.input.clone(),
+        std_dev_x: fe.std_dev_x,
+        std_dev_y: fe.std_dev_y,
+    };
+    if let Some((std_dx, std_dy, box_blur)) = resolve_std_dev(&blur, units, bbox, ts) {
+        if box_blur {
+            svgfilters::box_blur(std_dx, std_dy, into_svgfilters_image_mut!(buffer));
+        } else {
+            svgfilters::iir_blur(std_dx, std_dy, into_svgfilters_image_mut!(buffer));
+        }
+    }
+
+    let (dx, dy) = scale_coordinates(fe.dx, fe.dy, units, bbox, ts).unwrap_or((0.0, 0.0));
+    let mut shadow = create_image(region.width(), region.height())?;
+    shadow.draw_image_at(
+        dx as f32, dy as f32, &buffer.as_image(), &raqote::DrawOptions::default(),
+    );
+
+    let mut flood = create_image(region.width(), region.height())?;
+    let alpha = (fe.opacity.value() * 255.0) as u8;
+    flood.clear(fe.color.to_solid(alpha));
+
+    // Inputs are not used by `apply_composite`.
+    let composite = usvg::FeComposite {
+        input1: fe.input.clone(),
+        input2: fe.input.clone(),
+        operator: usvg::FeCompositeOperator::In,
+    };
+    let shadow = apply_composite(
+        &composite, cs, region,
+        Image::from_image(flood, ColorSpace::SRGB),
+        Image::from_image(shadow, cs),
+    )?;
+    let input = input.into_color_space(cs)?;
+
+    let mut dt = create_image(region.width(), region.height())?;
+    dt.draw_image_at(0.0, 0.0, &shadow.as_ref().as_image(), &raqote::DrawOptions::default());
+    dt.draw_image_at(0.0, 0.0, &input.as_ref().as_image(), &raqote::DrawOptions::default());
+
+    Ok(Image::from_image(dt, cs))
+}
+
 fn apply_image(
     fe: &usvg::FeImage,
     region: ScreenRect,
@@ -35,8 +35,10 @@ pub fn mask(
         mask_dt.pop_clip();
     }
 
-    use rgb::FromSlice;
-    image_to_mask(mask_dt.get_data_u8_mut().as_bgra_mut(), layers.image_size());
+    if mask.kind == usvg::MaskType::Luminance {
+        use rgb::FromSlice;
+        image_to_mask(mask_dt.get_data_u8_mut().as_bgra_mut(), layers.image_size());
+    }
 
     if let Some(ref id) = mask.mask {
         if let Some(ref mask_node) = node.tree().defs_by_id(id) {
@@ -56,7 +58,7 @@ pub fn mask(
     );
 }
 
-/// Converts an image into an alpha mask.
+/// Converts an image into an alpha mask using its luminance.
 fn image_to_mask(
     data: &mut [rgb::alt::BGRA8],
     img_size: ScreenSize,
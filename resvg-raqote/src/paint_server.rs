@@ -36,6 +36,21 @@ pub fn fill(
                             patt_dt = sub_dt;
                             create_pattern_image(&patt_dt, patt_ts)
                         }
+                        usvg::NodeKind::MeshGradient(ref mesh) => {
+                            let (sub_dt, mesh_ts) = try_opt!(
+                                prepare_mesh(mesh, dt, bbox, 0.0, fill.opacity)
+                            );
+                            patt_dt = sub_dt;
+                            create_pattern_image(&patt_dt, mesh_ts)
+                        }
+                        usvg::NodeKind::Hatch(ref hatch) => {
+                            let ts = *dt.get_transform();
+                            let (sub_dt, hatch_ts) = try_opt!(
+                                prepare_hatch(tree, hatch, ts, bbox, fill.opacity)
+                            );
+                            patt_dt = sub_dt;
+                            create_pattern_image(&patt_dt, hatch_ts)
+                        }
                         _ => {
                             return;
                         }
@@ -112,6 +127,23 @@ pub fn stroke(
                             patt_dt = sub_dt;
                             create_pattern_image(&patt_dt, patt_ts)
                         }
+                        usvg::NodeKind::MeshGradient(ref mesh) => {
+                            // A stroke can be painted outside the bbox.
+                            let margin = stroke.width.value() * stroke.miterlimit.value();
+                            let (sub_dt, mesh_ts) = try_opt!(
+                                prepare_mesh(mesh, dt, bbox, margin, stroke.opacity)
+                            );
+                            patt_dt = sub_dt;
+                            create_pattern_image(&patt_dt, mesh_ts)
+                        }
+                        usvg::NodeKind::Hatch(ref hatch) => {
+                            let ts = *dt.get_transform();
+                            let (sub_dt, hatch_ts) = try_opt!(
+                                prepare_hatch(tree, hatch, ts, bbox, stroke.opacity)
+                            );
+                            patt_dt = sub_dt;
+                            create_pattern_image(&patt_dt, hatch_ts)
+                        }
                         _ => {
                             return;
                         }
@@ -220,7 +252,7 @@ fn conv_stops(
 ) -> Vec<raqote::GradientStop> {
     let mut stops = Vec::new();
 
-    for stop in &g.stops {
+    for stop in g.srgb_stops().iter() {
         let alpha = stop.opacity.value() * opacity.value();
         stops.push(raqote::GradientStop {
             position: stop.offset.value() as f32,
@@ -265,7 +297,54 @@ fn prepare_pattern<'a>(
     let mut layers = Layers::new(img_size);
     render_group(pattern_node, &mut RenderState::Ok, &mut layers, &mut dt);
 
-    let img = if !opacity.is_default() {
+    let img = apply_opacity(dt, img_size, opacity);
+
+    let mut ts = usvg::Transform::default();
+    ts.append(&pattern.transform);
+    ts.translate(r.x(), r.y());
+    ts.scale(1.0 / sx, 1.0 / sy);
+
+    Some((img, ts))
+}
+
+fn prepare_hatch(
+    tree: &usvg::Tree,
+    hatch: &usvg::Hatch,
+    global_ts: raqote::Transform,
+    bbox: Rect,
+    opacity: usvg::Opacity,
+) -> Option<(raqote::DrawTarget, usvg::Transform)> {
+    let tile = hatch.tile(bbox)?;
+    let r = tile.rect;
+
+    let global_ts = usvg::Transform::from_native(&global_ts);
+    let (sx, sy) = global_ts.get_scale();
+
+    let img_size = Size::new(r.width() * sx, r.height() * sy)?.to_screen_size();
+    let mut dt = raqote::DrawTarget::new(img_size.width() as i32, img_size.height() as i32);
+
+    let scale_ts = raqote::Transform::create_scale(sx as f32, sy as f32);
+    for path in &tile.paths {
+        dt.set_transform(&scale_ts);
+        dt.transform(&path.transform.to_native());
+        crate::path::draw(tree, path, raqote::DrawOptions::default(), &mut dt);
+    }
+
+    let img = apply_opacity(dt, img_size, opacity);
+
+    let mut ts = tile.transform;
+    ts.translate(r.x(), r.y());
+    ts.scale(1.0 / sx, 1.0 / sy);
+
+    Some((img, ts))
+}
+
+fn apply_opacity(
+    dt: raqote::DrawTarget,
+    img_size: ScreenSize,
+    opacity: usvg::Opacity,
+) -> raqote::DrawTarget {
+    if !opacity.is_default() {
         // If `opacity` isn't `1` then we have to make image semitransparent.
         // The only way to do this is by making a new image and rendering
         // the pattern on it with transparency.
@@ -280,14 +359,30 @@ fn prepare_pattern<'a>(
         img2
     } else {
         dt
-    };
+    }
+}
 
-    let mut ts = usvg::Transform::default();
-    ts.append(&pattern.transform);
-    ts.translate(r.x(), r.y());
-    ts.scale(1.0 / sx, 1.0 / sy);
+fn prepare_mesh(
+    mesh: &usvg::MeshGradient,
+    canvas: &raqote::DrawTarget,
+    bbox: Rect,
+    margin: f64,
+    opacity: usvg::Opacity,
+) -> Option<(raqote::DrawTarget, usvg::Transform)> {
+    let global_ts = usvg::Transform::from_native(canvas.get_transform());
+    let canvas_size = ScreenSize::new(canvas.width() as u32, canvas.height() as u32)?;
+    let img = mesh.rasterize(bbox, margin, global_ts, canvas_size, opacity)?;
+
+    let mut dt = raqote::DrawTarget::new(img.size.width() as i32, img.size.height() as i32);
+    let img_ts = img.transform;
+    let img = crate::image::Image {
+        data: crate::image::ImageData::RGBA(img.data),
+        size: img.size,
+    };
+    crate::image::image_to_surface(&img, dt.get_data_u8_mut());
 
-    Some((img, ts))
+    // The image covers the whole painted area, so its repeating will not be visible.
+    Some((dt, img_ts))
 }
 
 fn create_pattern_image(
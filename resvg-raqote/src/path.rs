@@ -34,13 +34,21 @@ pub fn draw(
         }
     }
 
+    let ts = usvg::Transform::from_native(dt.get_transform());
+    let stroke = path.stroke_with_transform(ts);
+
     let mut draw_opt = draw_opt.clone();
     if !path.rendering_mode.use_shape_antialiasing() {
         draw_opt.antialias = raqote::AntialiasMode::None;
     }
 
-    crate::paint_server::fill(tree, &new_path, &path.fill, style_bbox, &draw_opt, dt);
-    crate::paint_server::stroke(tree, &new_path, &path.stroke, style_bbox, &draw_opt, dt);
+    if path.paint_order.is_stroke_first() {
+        crate::paint_server::stroke(tree, &new_path, &stroke, style_bbox, &draw_opt, dt);
+        crate::paint_server::fill(tree, &new_path, &path.fill, style_bbox, &draw_opt, dt);
+    } else {
+        crate::paint_server::fill(tree, &new_path, &path.fill, style_bbox, &draw_opt, dt);
+        crate::paint_server::stroke(tree, &new_path, &stroke, style_bbox, &draw_opt, dt);
+    }
 
     bbox
 }
@@ -265,7 +265,9 @@ fn render_group_impl(
 
     // Filter can be rendered on an object without a bbox,
     // as long as filter uses `userSpaceOnUse`.
-    if let Some(ref id) = g.filter {
+    //
+    // Each filter is applied to the result of the previous one.
+    for id in &g.filters {
         if let Some(filter_node) = node.tree().defs_by_id(id) {
             if let usvg::NodeKind::Filter(ref filter) = *filter_node.borrow() {
                 let ts = usvg::Transform::from_native(&curr_ts);
@@ -300,19 +302,52 @@ fn render_group_impl(
         }
     }
 
-    dt.blend_surface_with_alpha(
-        &sub_dt,
-        raqote::IntRect::new(
+    if g.blend_mode == usvg::BlendMode::Normal {
+        dt.blend_surface_with_alpha(
+            &sub_dt,
+            raqote::IntRect::new(
+                raqote::IntPoint::new(0, 0),
+                raqote::IntPoint::new(sub_dt.width(), sub_dt.height())
+            ),
             raqote::IntPoint::new(0, 0),
-            raqote::IntPoint::new(sub_dt.width(), sub_dt.height())
-        ),
-        raqote::IntPoint::new(0, 0),
-        g.opacity.value() as f32
-    );
+            g.opacity.value() as f32
+        );
+    } else {
+        let draw_opt = raqote::DrawOptions {
+            blend_mode: convert_blend_mode(g.blend_mode),
+            alpha: g.opacity.value() as f32,
+            ..raqote::DrawOptions::default()
+        };
+
+        dt.set_transform(&raqote::Transform::default());
+        dt.draw_image_at(0.0, 0.0, &sub_dt.as_image(), &draw_opt);
+        dt.set_transform(&curr_ts);
+    }
 
     bbox
 }
 
+fn convert_blend_mode(mode: usvg::BlendMode) -> raqote::BlendMode {
+    match mode {
+        usvg::BlendMode::Normal => raqote::BlendMode::SrcOver,
+        usvg::BlendMode::Multiply => raqote::BlendMode::Multiply,
+        usvg::BlendMode::Screen => raqote::BlendMode::Screen,
+        usvg::BlendMode::Overlay => raqote::BlendMode::Overlay,
+        usvg::BlendMode::Darken => raqote::BlendMode::Darken,
+        usvg::BlendMode::Lighten => raqote::BlendMode::Lighten,
+        usvg::BlendMode::ColorDodge => raqote::BlendMode::ColorDodge,
+        usvg::BlendMode::ColorBurn => raqote::BlendMode::ColorBurn,
+        usvg::BlendMode::HardLight => raqote::BlendMode::HardLight,
+        usvg::BlendMode::SoftLight => raqote::BlendMode::SoftLight,
+        usvg::BlendMode::Difference => raqote::BlendMode::Difference,
+        usvg::BlendMode::Exclusion => raqote::BlendMode::Exclusion,
+        usvg::BlendMode::Hue => raqote::BlendMode::Hue,
+        usvg::BlendMode::Saturation => raqote::BlendMode::Saturation,
+        usvg::BlendMode::Color => raqote::BlendMode::Color,
+        usvg::BlendMode::Luminosity => raqote::BlendMode::Luminosity,
+    }
+}
+
 /// Renders an image used by `BackgroundImage` or `BackgroundAlpha` filter inputs.
 fn prepare_filter_background(
     parent: &usvg::Node,
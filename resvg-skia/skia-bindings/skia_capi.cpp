@@ -41,6 +41,17 @@ static SkBlendMode blendModes_[static_cast<int>(BlendMode::__Size)] = {
     SkBlendMode::kScreen,
     SkBlendMode::kDarken,
     SkBlendMode::kLighten,
+    SkBlendMode::kOverlay,
+    SkBlendMode::kColorDodge,
+    SkBlendMode::kColorBurn,
+    SkBlendMode::kHardLight,
+    SkBlendMode::kSoftLight,
+    SkBlendMode::kDifference,
+    SkBlendMode::kExclusion,
+    SkBlendMode::kHue,
+    SkBlendMode::kSaturation,
+    SkBlendMode::kColor,
+    SkBlendMode::kLuminosity,
 };
 
 extern "C" {
@@ -209,6 +220,16 @@ skiac_matrix* skiac_canvas_get_total_matrix(skiac_canvas* c_canvas)
     return reinterpret_cast<skiac_matrix*>(matrix);
 }
 
+int skiac_canvas_get_width(skiac_canvas* c_canvas)
+{
+    return CANVAS_CAST->getBaseLayerSize().width();
+}
+
+int skiac_canvas_get_height(skiac_canvas* c_canvas)
+{
+    return CANVAS_CAST->getBaseLayerSize().height();
+}
+
 void skiac_canvas_draw_path(skiac_canvas* c_canvas, skiac_path* c_path, skiac_paint* c_paint)
 {
     CANVAS_CAST->drawPath(*PATH_CAST, *PAINT_CAST);
@@ -83,6 +83,17 @@ enum class BlendMode {
     Screen = 10,
     Darken = 11,
     Lighten = 12,
+    Overlay = 13,
+    ColorDodge = 14,
+    ColorBurn = 15,
+    HardLight = 16,
+    SoftLight = 17,
+    Difference = 18,
+    Exclusion = 19,
+    Hue = 20,
+    Saturation = 21,
+    Color = 22,
+    Luminosity = 23,
     __Size
 };
 
@@ -116,6 +127,8 @@ void skiac_canvas_concat(skiac_canvas* c_canvas, skiac_matrix* c_matrix);
 void skiac_canvas_scale(skiac_canvas* c_canvas, double sx, double sy);
 void skiac_canvas_translate(skiac_canvas* c_canvas, double dx, double dy);
 skiac_matrix* skiac_canvas_get_total_matrix(skiac_canvas* c_canvas);
+int skiac_canvas_get_width(skiac_canvas* c_canvas);
+int skiac_canvas_get_height(skiac_canvas* c_canvas);
 void skiac_canvas_draw_path(skiac_canvas* c_canvas, skiac_path* c_path, skiac_paint* c_paint);
 void skiac_canvas_draw_rect(skiac_canvas* c_canvas, double x, double y, double w, double h, skiac_paint* c_paint);
 void skiac_canvas_draw_surface(skiac_canvas* c_canvas, skiac_surface* c_surface, double left, double top,
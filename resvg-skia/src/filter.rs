@@ -318,6 +318,10 @@ fn _apply(
                 let input = get_input(&fe.input, region, inputs, &results)?;
                 apply_offset(fe, filter.primitive_units, bbox, ts, input)
             }
+            usvg::FilterKind::FeDropShadow(ref fe) => {
+                let input = get_input(&fe.input, region, inputs, &results)?;
+                apply_drop_shadow(fe, filter.primitive_units, cs, region, bbox, ts, input)
+            }
             usvg::FilterKind::FeComposite(ref fe) => {
                 let input1 = get_input(&fe.input1, region, inputs, &results)?;
                 let input2 = get_input(&fe.input2, region, inputs, &results)?;
@@ -746,6 +750,52 @@ fn apply_flood(
     Ok(Image::from_image(buffer, ColorSpace::SRGB))
 }
 
+fn apply_drop_shadow(
+    fe: &usvg::FeDropShadow,
+    units: usvg::Units,
+    cs: ColorSpace,
+    region: ScreenRect,
+    bbox: Option<Rect>,
+    ts: &usvg::Transform,
+    input: Image,
+) -> Result<Image, Error> {
+    let blur = usvg::FeGaussianBlur {
+        input: fe.input.clone(),
+        std_dev_x: fe.std_dev_x,
+        std_dev_y: fe.std_dev_y,
+    };
+    let shadow = apply_blur(&blur, units, cs, bbox, ts, input.clone())?;
+
+    let offset = usvg::FeOffset {
+        input: fe.input.clone(),
+        dx: fe.dx,
+        dy: fe.dy,
+    };
+    let shadow = apply_offset(&offset, units, bbox, ts, shadow)?;
+
+    let flood = apply_flood(&usvg::FeFlood { color: fe.color, opacity: fe.opacity }, region)?;
+
+    // Inputs are not used by `apply_composite`.
+    let composite = usvg::FeComposite {
+        input1: fe.input.clone(),
+        input2: fe.input.clone(),
+        operator: usvg::FeCompositeOperator::In,
+    };
+    let shadow = apply_composite(&composite, cs, region, flood, shadow)?;
+    let input = input.into_color_space(cs)?;
+
+    let mut buffer = skia::Surface::try_create(region.width(), region.height())?;
+    buffer.reset_matrix();
+
+    buffer.draw_surface(shadow.as_ref(), 0.0, 0.0, 255, skia::BlendMode::SourceOver,
+                        skia::FilterQuality::Low);
+    buffer.draw_surface(input.as_ref(), 0.0, 0.0, 255, skia::BlendMode::SourceOver,
+                        skia::FilterQuality::Low);
+    buffer.flush();
+
+    Ok(Image::from_image(buffer, cs))
+}
+
 fn apply_tile(
     input: Image,
     region: ScreenRect,
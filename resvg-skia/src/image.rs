@@ -79,7 +79,7 @@ fn draw_raster(
     canvas.restore();
 }
 
-fn image_to_surface(image: &Image, surface: &mut [u8]) {
+pub(crate) fn image_to_surface(image: &Image, surface: &mut [u8]) {
     // Surface is always ARGB.
     const SURFACE_CHANNELS: usize = 4;
 
@@ -156,19 +156,21 @@ fn draw_svg(
 }
 
 /// A raster image data.
-struct Image {
-    pub data: ImageData,
-    pub size: ScreenSize,
+pub(crate) struct Image {
+    pub(crate) data: ImageData,
+    pub(crate) size: ScreenSize,
 }
 
 
 /// A raster image data kind.
-enum ImageData {
+pub(crate) enum ImageData {
     RGB(Vec<u8>),
     RGBA(Vec<u8>),
 }
 
 fn read_png(data: &[u8]) -> Option<Image> {
+    let profile = usvg::ColorProfile::from_png(data);
+
     let decoder = png::Decoder::new(data);
     let (info, mut reader) = decoder.read_info().ok()?;
 
@@ -177,7 +179,7 @@ fn read_png(data: &[u8]) -> Option<Image> {
     let mut img_data = vec![0; info.buffer_size()];
     reader.next_frame(&mut img_data).ok()?;
 
-    let data = match info.color_type {
+    let mut data = match info.color_type {
         png::ColorType::RGB => ImageData::RGB(img_data),
         png::ColorType::RGBA => ImageData::RGBA(img_data),
         png::ColorType::Grayscale => {
@@ -209,6 +211,10 @@ fn read_png(data: &[u8]) -> Option<Image> {
         }
     };
 
+    if let Some(profile) = profile {
+        convert_to_srgb(&mut data, &profile);
+    }
+
     Some(Image {
         data,
         size,
@@ -216,13 +222,15 @@ fn read_png(data: &[u8]) -> Option<Image> {
 }
 
 fn read_jpeg(data: &[u8]) -> Option<Image> {
+    let profile = usvg::ColorProfile::from_jpeg(data);
+
     let mut decoder = jpeg_decoder::Decoder::new(data);
     let img_data = decoder.decode().ok()?;
     let info = decoder.info()?;
 
     let size = ScreenSize::new(info.width as u32, info.height as u32)?;
 
-    let data = match info.pixel_format {
+    let mut data = match info.pixel_format {
         jpeg_decoder::PixelFormat::RGB24 => ImageData::RGB(img_data),
         jpeg_decoder::PixelFormat::L8 => {
             let mut rgb_data = Vec::with_capacity(img_data.len() * 3);
@@ -237,12 +245,23 @@ fn read_jpeg(data: &[u8]) -> Option<Image> {
         _ => return None,
     };
 
+    if let Some(profile) = profile {
+        convert_to_srgb(&mut data, &profile);
+    }
+
     Some(Image {
         data,
         size,
     })
 }
 
+fn convert_to_srgb(data: &mut ImageData, profile: &usvg::ColorProfile) {
+    match data {
+        ImageData::RGB(data) => profile.convert_rgb(data),
+        ImageData::RGBA(data) => profile.convert_rgba(data),
+    }
+}
+
 /// Calculates an image rect depending on the provided view box.
 fn image_rect(
     view_box: &usvg::ViewBox,
@@ -35,7 +35,7 @@ pub fn mask(
         mask_surface.restore();
     }
 
-    {
+    if mask.kind == usvg::MaskType::Luminance {
         use rgb::FromSlice;
         use std::mem::swap;
 
@@ -68,7 +68,7 @@ pub fn mask(
     );
 }
 
-/// Converts an image into an alpha mask.
+/// Converts an image into an alpha mask using its luminance.
 fn image_to_mask(
     data: &mut [rgb::alt::BGRA8],
     img_size: ScreenSize,
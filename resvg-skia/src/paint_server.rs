@@ -9,6 +9,7 @@ pub fn fill(
     fill: &Option<usvg::Fill>,
     bbox: Rect,
     global_ts: usvg::Transform,
+    canvas_size: ScreenSize,
 ) -> skia::Paint {
     let mut paint = skia::Paint::new();
     paint.set_style(skia::PaintStyle::Fill);
@@ -32,6 +33,12 @@ pub fn fill(
                         usvg::NodeKind::Pattern(ref pattern) => {
                             prepare_pattern(&node, pattern, global_ts, bbox, opacity, &mut paint);
                         }
+                        usvg::NodeKind::MeshGradient(ref mesh) => {
+                            prepare_mesh(mesh, global_ts, canvas_size, bbox, 0.0, opacity, &mut paint);
+                        }
+                        usvg::NodeKind::Hatch(ref hatch) => {
+                            prepare_hatch(tree, hatch, global_ts, bbox, opacity, &mut paint);
+                        }
                         _ => {}
                     }
                 }
@@ -47,6 +54,7 @@ pub fn stroke(
     stroke: &Option<usvg::Stroke>,
     bbox: Rect,
     global_ts: usvg::Transform,
+    canvas_size: ScreenSize,
 ) -> skia::Paint {
     let mut paint = skia::Paint::new();
     paint.set_style(skia::PaintStyle::Stroke);
@@ -70,6 +78,14 @@ pub fn stroke(
                         usvg::NodeKind::Pattern(ref pattern) => {
                             prepare_pattern(&node, pattern, global_ts, bbox, opacity, &mut paint);
                         }
+                        usvg::NodeKind::MeshGradient(ref mesh) => {
+                            // A stroke can be painted outside the bbox.
+                            let margin = stroke.width.value() * stroke.miterlimit.value();
+                            prepare_mesh(mesh, global_ts, canvas_size, bbox, margin, opacity, &mut paint);
+                        }
+                        usvg::NodeKind::Hatch(ref hatch) => {
+                            prepare_hatch(tree, hatch, global_ts, bbox, opacity, &mut paint);
+                        }
                         _ => {}
                     }
                 }
@@ -161,7 +177,7 @@ fn prepare_base_gradient(
     let mut colors: Vec<u32> = Vec::new();
     let mut positions: Vec<f32> = Vec::new();
 
-    for stop in &g.stops {
+    for stop in g.srgb_stops().iter() {
         let a = (stop.opacity.value() * opacity.value() * 255.0) as u8;
         let color = skia::Color::new(a, stop.color.red, stop.color.green, stop.color.blue);
         colors.push(color.to_u32());
@@ -218,3 +234,69 @@ fn prepare_pattern(
         paint.set_alpha(a);
     };
 }
+
+fn prepare_hatch(
+    tree: &usvg::Tree,
+    hatch: &usvg::Hatch,
+    global_ts: usvg::Transform,
+    bbox: Rect,
+    opacity: usvg::Opacity,
+    paint: &mut skia::Paint,
+) {
+    let tile = try_opt!(hatch.tile(bbox));
+    let r = tile.rect;
+
+    let (sx, sy) = global_ts.get_scale();
+
+    let img_size = try_opt!(Size::new(r.width() * sx, r.height() * sy)).to_screen_size();
+    let mut surface = try_opt!(crate::render::create_subsurface(img_size));
+    surface.clear();
+
+    surface.scale(sx, sy);
+    let scale_ts = surface.get_matrix();
+    for path in &tile.paths {
+        surface.set_matrix(&scale_ts);
+        surface.concat(&path.transform.to_native());
+        crate::path::draw(tree, path, skia::BlendMode::SourceOver, &mut surface);
+    }
+
+    let mut ts = tile.transform;
+    ts.translate(r.x(), r.y());
+    ts.scale(1.0 / sx, 1.0 / sy);
+    let shader = skia::Shader::new_from_surface_image(&surface, ts.to_native());
+    paint.set_shader(&shader);
+
+    if !opacity.is_default() {
+        let a = usvg::utils::f64_bound(0.0, opacity.value() * 255.0, 255.0) as u8;
+        paint.set_alpha(a);
+    };
+}
+
+fn prepare_mesh(
+    mesh: &usvg::MeshGradient,
+    global_ts: usvg::Transform,
+    canvas_size: ScreenSize,
+    bbox: Rect,
+    margin: f64,
+    opacity: usvg::Opacity,
+    paint: &mut skia::Paint,
+) {
+    let img = try_opt!(mesh.rasterize(bbox, margin, global_ts, canvas_size, opacity));
+
+    let (w, h) = img.size.dimensions();
+    let mut surface = try_opt_warn_or!(
+        skia::Surface::new_rgba(w, h), (),
+        "Failed to create a {}x{} surface.", w, h
+    );
+
+    let img_ts = img.transform;
+    let img = crate::image::Image {
+        data: crate::image::ImageData::RGBA(img.data),
+        size: img.size,
+    };
+    crate::image::image_to_surface(&img, &mut surface.data_mut());
+
+    // The image covers the whole painted area, so its repeating will not be visible.
+    let shader = skia::Shader::new_from_surface_image(&surface, img_ts.to_native());
+    paint.set_shader(&shader);
+}
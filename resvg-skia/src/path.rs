@@ -30,19 +30,37 @@ pub fn draw(
     let antialias = path.rendering_mode.use_shape_antialiasing();
 
     let global_ts = usvg::Transform::from_native(&canvas.get_matrix());
+    let canvas_size = try_opt_or!(ScreenSize::new(canvas.width(), canvas.height()), bbox);
 
+    let mut fill = None;
     if path.fill.is_some() {
-        let mut fill = crate::paint_server::fill(tree, &path.fill, style_bbox, global_ts);
-        fill.set_anti_alias(antialias);
-        fill.set_blend_mode(blend_mode);
-        canvas.draw_path(&skia_path, &fill);
+        let mut paint = crate::paint_server::fill(
+            tree, &path.fill, style_bbox, global_ts, canvas_size,
+        );
+        paint.set_anti_alias(antialias);
+        paint.set_blend_mode(blend_mode);
+        fill = Some(paint);
     }
 
-    if path.stroke.is_some() {
-        let mut stroke = crate::paint_server::stroke(tree, &path.stroke, style_bbox, global_ts);
-        stroke.set_anti_alias(antialias);
-        stroke.set_blend_mode(blend_mode);
-        canvas.draw_path(&skia_path, &stroke);
+    let mut stroke = None;
+    let path_stroke = path.stroke_with_transform(global_ts);
+    if path_stroke.is_some() {
+        let mut paint = crate::paint_server::stroke(
+            tree, &path_stroke, style_bbox, global_ts, canvas_size,
+        );
+        paint.set_anti_alias(antialias);
+        paint.set_blend_mode(blend_mode);
+        stroke = Some(paint);
+    }
+
+    let paints = if path.paint_order.is_stroke_first() {
+        [stroke, fill]
+    } else {
+        [fill, stroke]
+    };
+
+    for paint in paints.iter().flatten() {
+        canvas.draw_path(&skia_path, paint);
     }
 
     bbox
@@ -192,7 +192,9 @@ fn render_group_impl(
 
     // Filter can be rendered on an object without a bbox,
     // as long as filter uses `userSpaceOnUse`.
-    if let Some(ref id) = g.filter {
+    //
+    // Each filter is applied to the result of the previous one.
+    for id in &g.filters {
         if let Some(filter_node) = node.tree().defs_by_id(id) {
             if let usvg::NodeKind::Filter(ref filter) = *filter_node.borrow() {
                 let ts = usvg::Transform::from_native(&curr_ts);
@@ -236,13 +238,34 @@ fn render_group_impl(
     let curr_ts = canvas.get_matrix();
     canvas.reset_matrix();
     canvas.draw_surface(
-        &sub_surface, 0.0, 0.0, a, skia::BlendMode::SourceOver, skia::FilterQuality::Low,
+        &sub_surface, 0.0, 0.0, a, convert_blend_mode(g.blend_mode), skia::FilterQuality::Low,
     );
     canvas.set_matrix(&curr_ts);
 
     bbox
 }
 
+fn convert_blend_mode(mode: usvg::BlendMode) -> skia::BlendMode {
+    match mode {
+        usvg::BlendMode::Normal => skia::BlendMode::SourceOver,
+        usvg::BlendMode::Multiply => skia::BlendMode::Multiply,
+        usvg::BlendMode::Screen => skia::BlendMode::Screen,
+        usvg::BlendMode::Overlay => skia::BlendMode::Overlay,
+        usvg::BlendMode::Darken => skia::BlendMode::Darken,
+        usvg::BlendMode::Lighten => skia::BlendMode::Lighten,
+        usvg::BlendMode::ColorDodge => skia::BlendMode::ColorDodge,
+        usvg::BlendMode::ColorBurn => skia::BlendMode::ColorBurn,
+        usvg::BlendMode::HardLight => skia::BlendMode::HardLight,
+        usvg::BlendMode::SoftLight => skia::BlendMode::SoftLight,
+        usvg::BlendMode::Difference => skia::BlendMode::Difference,
+        usvg::BlendMode::Exclusion => skia::BlendMode::Exclusion,
+        usvg::BlendMode::Hue => skia::BlendMode::Hue,
+        usvg::BlendMode::Saturation => skia::BlendMode::Saturation,
+        usvg::BlendMode::Color => skia::BlendMode::Color,
+        usvg::BlendMode::Luminosity => skia::BlendMode::Luminosity,
+    }
+}
+
 /// Renders an image used by `BackgroundImage` or `BackgroundAlpha` filter inputs.
 fn prepare_filter_background(
     parent: &usvg::Node,
@@ -282,7 +305,10 @@ fn prepare_filter_fill_paint(
         if let Some(paint) = g.filter_fill.clone() {
             let style_bbox = bbox.unwrap_or_else(|| Rect::new(0.0, 0.0, 1.0, 1.0).unwrap());
             let fill = Some(usvg::Fill::from_paint(paint));
-            let fill = crate::paint_server::fill(&parent.tree(), &fill, style_bbox, ts);
+            let canvas_size = ScreenSize::new(canvas.width(), canvas.height())?;
+            let fill = crate::paint_server::fill(
+                &parent.tree(), &fill, style_bbox, ts, canvas_size,
+            );
             surface.draw_rect(0.0, 0.0, region.width() as f64, region.height() as f64, &fill);
         }
     }
@@ -304,7 +330,10 @@ fn prepare_filter_stroke_paint(
         if let Some(paint) = g.filter_stroke.clone() {
             let style_bbox = bbox.unwrap_or_else(|| Rect::new(0.0, 0.0, 1.0, 1.0).unwrap());
             let fill = Some(usvg::Fill::from_paint(paint));
-            let fill = crate::paint_server::fill(&parent.tree(), &fill, style_bbox, ts);
+            let canvas_size = ScreenSize::new(canvas.width(), canvas.height())?;
+            let fill = crate::paint_server::fill(
+                &parent.tree(), &fill, style_bbox, ts, canvas_size,
+            );
             surface.draw_rect(0.0, 0.0, region.width() as f64, region.height() as f64, &fill);
         }
     }
@@ -110,6 +110,8 @@ mod ffi {
         pub fn skiac_canvas_scale(c_canvas: *mut skiac_canvas, sx: f64, sy: f64);
         pub fn skiac_canvas_translate(c_canvas: *mut skiac_canvas, dx: f64, dy: f64);
         pub fn skiac_canvas_get_total_matrix(c_canvas: *mut skiac_canvas) -> *mut skiac_matrix;
+        pub fn skiac_canvas_get_width(c_canvas: *mut skiac_canvas) -> ::std::os::raw::c_int;
+        pub fn skiac_canvas_get_height(c_canvas: *mut skiac_canvas) -> ::std::os::raw::c_int;
         pub fn skiac_canvas_draw_path(
             c_canvas: *mut skiac_canvas,
             c_path: *mut skiac_path,
@@ -272,6 +274,17 @@ pub enum BlendMode {
     Screen = 10,
     Darken = 11,
     Lighten = 12,
+    Overlay = 13,
+    ColorDodge = 14,
+    ColorBurn = 15,
+    HardLight = 16,
+    SoftLight = 17,
+    Difference = 18,
+    Exclusion = 19,
+    Hue = 20,
+    Saturation = 21,
+    Color = 22,
+    Luminosity = 23,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -505,6 +518,14 @@ impl Canvas {
         unsafe { Matrix(ffi::skiac_canvas_get_total_matrix(self.0)) }
     }
 
+    pub fn width(&self) -> u32 {
+        unsafe { ffi::skiac_canvas_get_width(self.0) as u32 }
+    }
+
+    pub fn height(&self) -> u32 {
+        unsafe { ffi::skiac_canvas_get_height(self.0) as u32 }
+    }
+
     pub fn draw_path(&mut self, path: &Path, paint: &Paint) {
         unsafe { ffi::skiac_canvas_draw_path(self.0, path.0, paint.0); }
     }
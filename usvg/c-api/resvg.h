@@ -58,8 +58,8 @@ typedef enum resvg_error {
      *
      * Occurs when width and/or height are <= 0.
      *
-     * Also occurs if width, height and viewBox are not set.
-     * This is against the SVG spec, but an automatic size detection is not supported yet.
+     * Also occurs if width, height and viewBox are not set
+     * and the document doesn't have any renderable content.
      */
     RESVG_ERROR_INVALID_SIZE,
     /** Failed to parse an SVG data. */
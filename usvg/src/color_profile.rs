@@ -0,0 +1,548 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::io::Read;
+
+use log::warn;
+
+type Matrix = [[f64; 3]; 3];
+
+/// sRGB primaries adapted to D50.
+const SRGB_TO_XYZ_D50: Matrix = [
+    [0.4360747, 0.3850649, 0.1430804],
+    [0.2225045, 0.7168786, 0.0606169],
+    [0.0139322, 0.0971045, 0.7141733],
+];
+
+const XYZ_D50_TO_SRGB: Matrix = [
+    [ 3.1338561, -1.6168667, -0.4906146],
+    [-0.9787684,  1.9161415,  0.0334540],
+    [ 0.0719453, -0.2289914,  1.4052427],
+];
+
+const D50: [f64; 3] = [0.9642, 1.0, 0.8249];
+
+const BRADFORD: Matrix = [
+    [ 0.8951,  0.2664, -0.1614],
+    [-0.7502,  1.7135,  0.0367],
+    [ 0.0389, -0.0685,  1.0296],
+];
+
+/// A size of the linear to sRGB lookup table.
+const ENCODE_TABLE_SIZE: usize = 4096;
+
+/// A maximum size of a decompressed PNG color profile.
+///
+/// Real profiles are much smaller, so anything bigger is likely a decompression bomb.
+const MAX_ICC_SIZE: u64 = 4 * 1024 * 1024;
+
+
+/// A raster image color profile.
+///
+/// Converts decoded pixels of an embedded PNG or JPEG image into the sRGB color space.
+///
+/// Only matrix/TRC and grayscale ICC profiles are supported.
+/// LUT-based and CMYK profiles are ignored.
+#[derive(Clone)]
+pub struct ColorProfile {
+    /// Decoding tables for each channel, from an 8-bit value to a linear one.
+    curves: [Vec<f32>; 3],
+
+    /// A transform from the profile linear RGB to the linear sRGB.
+    ///
+    /// `None` for grayscale profiles.
+    matrix: Option<Matrix>,
+}
+
+impl std::fmt::Debug for ColorProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("ColorProfile(..)")
+    }
+}
+
+impl ColorProfile {
+    /// Extracts a color profile from a PNG data.
+    ///
+    /// Chunks are resolved in the order of preference: `sRGB`, `iCCP` and then `gAMA`/`cHRM`.
+    ///
+    /// Returns `None` when the image is already in sRGB, has no color information
+    /// or it cannot be processed.
+    pub fn from_png(data: &[u8]) -> Option<Self> {
+        if !data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            return None;
+        }
+
+        let mut icc = None;
+        let mut gamma = None;
+        let mut chromaticities = None;
+
+        let mut offset = 8;
+        while let Some(len) = read_u32(data, offset) {
+            let chunk_type = data.get(offset + 4..offset + 8)?;
+            let chunk = data.get(offset + 8..offset + 8 + len as usize)?;
+            match chunk_type {
+                b"sRGB" => return None,
+                b"iCCP" => icc = Some(chunk),
+                b"gAMA" => gamma = read_u32(chunk, 0).map(|n| n as f64 / 100000.0),
+                b"cHRM" => {
+                    let mut values = [0.0; 8];
+                    for (i, v) in values.iter_mut().enumerate() {
+                        *v = read_u32(chunk, i * 4)? as f64 / 100000.0;
+                    }
+
+                    chromaticities = Some(values);
+                }
+                b"IDAT" | b"IEND" => break,
+                _ => {}
+            }
+
+            // Skip length, type and CRC.
+            offset += len as usize + 12;
+        }
+
+        if let Some(chunk) = icc {
+            // Profile name, null separator, compression method and a compressed profile.
+            let name_end = chunk.iter().position(|c| *c == 0)?;
+            let compressed = chunk.get(name_end + 2..)?;
+
+            let mut profile = Vec::new();
+            let decoder = flate2::read::ZlibDecoder::new(compressed);
+            if decoder.take(MAX_ICC_SIZE).read_to_end(&mut profile).is_err() {
+                warn!("Failed to decompress a PNG color profile.");
+                return None;
+            }
+
+            if profile.len() as u64 == MAX_ICC_SIZE {
+                warn!("A PNG color profile is too big.");
+                return None;
+            }
+
+            return Self::from_icc(&profile);
+        }
+
+        if gamma.is_none() && chromaticities.is_none() {
+            return None;
+        }
+
+        // `gAMA` stores an encoding exponent.
+        let gamma = match gamma {
+            Some(gamma) if gamma > 0.0 => 1.0 / gamma,
+            _ => 2.2,
+        };
+
+        let curve = build_table(|x| x.powf(gamma));
+
+        let matrix = match chromaticities {
+            Some(values) => chromaticities_to_xyz_d50(values)?,
+            None => SRGB_TO_XYZ_D50,
+        };
+
+        Self::new([curve.clone(), curve.clone(), curve], Some(matrix))
+    }
+
+    /// Extracts a color profile from a JPEG data.
+    ///
+    /// The profile is stored in `APP2` markers and can be split between multiple of them.
+    ///
+    /// Returns `None` when the image has no profile, the profile is sRGB
+    /// or it cannot be processed.
+    pub fn from_jpeg(data: &[u8]) -> Option<Self> {
+        const ICC_SIGNATURE: &[u8] = b"ICC_PROFILE\0";
+
+        if !data.starts_with(&[0xff, 0xd8]) {
+            return None;
+        }
+
+        let mut chunks = Vec::new();
+        let mut offset = 2;
+        loop {
+            if *data.get(offset)? != 0xff {
+                return None;
+            }
+
+            let marker = *data.get(offset + 1)?;
+            match marker {
+                // Padding.
+                0xff => {
+                    offset += 1;
+                    continue;
+                }
+                // Markers without a payload.
+                0x01 | 0xd0..=0xd7 => {
+                    offset += 2;
+                    continue;
+                }
+                // Start of scan or end of image.
+                0xda | 0xd9 => break,
+                _ => {}
+            }
+
+            let len = read_u16(data, offset + 2)? as usize;
+            let segment = data.get(offset + 4..offset + 2 + len)?;
+            if marker == 0xe2 && segment.starts_with(ICC_SIGNATURE) {
+                let seq = *segment.get(ICC_SIGNATURE.len())?;
+                let chunk = segment.get(ICC_SIGNATURE.len() + 2..)?;
+                chunks.push((seq, chunk));
+            }
+
+            offset += 2 + len;
+        }
+
+        if chunks.is_empty() {
+            return None;
+        }
+
+        chunks.sort_by_key(|(seq, _)| *seq);
+
+        let mut profile = Vec::new();
+        for (_, chunk) in chunks {
+            profile.extend_from_slice(chunk);
+        }
+
+        Self::from_icc(&profile)
+    }
+
+    /// Parses an ICC profile.
+    ///
+    /// Returns `None` when the profile is sRGB or it cannot be processed.
+    pub fn from_icc(data: &[u8]) -> Option<Self> {
+        let color_space = data.get(16..20)?;
+        let pcs = data.get(20..24)?;
+
+        match color_space {
+            b"RGB " => {
+                if pcs != b"XYZ " {
+                    warn!("ICC profiles with a Lab connection space are not supported.");
+                    return None;
+                }
+
+                let curves = [
+                    parse_curve_tag(data, b"rTRC")?,
+                    parse_curve_tag(data, b"gTRC")?,
+                    parse_curve_tag(data, b"bTRC")?,
+                ];
+
+                let r = parse_xyz_tag(data, b"rXYZ")?;
+                let g = parse_xyz_tag(data, b"gXYZ")?;
+                let b = parse_xyz_tag(data, b"bXYZ")?;
+                let matrix = [
+                    [r[0], g[0], b[0]],
+                    [r[1], g[1], b[1]],
+                    [r[2], g[2], b[2]],
+                ];
+
+                Self::new(curves, Some(matrix))
+            }
+            b"GRAY" => {
+                let curve = parse_curve_tag(data, b"kTRC")?;
+                Self::new([curve.clone(), curve.clone(), curve], None)
+            }
+            _ => {
+                warn!("Only RGB and grayscale ICC profiles are supported.");
+                None
+            }
+        }
+    }
+
+    /// Creates a new profile from decoding curves and a profile to XYZ D50 matrix.
+    fn new(curves: [Vec<f32>; 3], to_xyz_d50: Option<Matrix>) -> Option<Self> {
+        let matrix = to_xyz_d50.map(|m| mul_matrix(&XYZ_D50_TO_SRGB, &m));
+
+        let profile = ColorProfile { curves, matrix };
+        if profile.is_srgb() {
+            return None;
+        }
+
+        Some(profile)
+    }
+
+    /// Checks that the profile doesn't change pixels.
+    fn is_srgb(&self) -> bool {
+        if let Some(ref m) = self.matrix {
+            for (y, row) in m.iter().enumerate() {
+                for (x, n) in row.iter().enumerate() {
+                    let expected = if x == y { 1.0 } else { 0.0 };
+                    if (n - expected).abs() > 0.002 {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        let srgb = build_table(srgb_to_linear);
+        self.curves.iter().all(|curve| {
+            curve.iter().zip(srgb.iter()).all(|(a, b)| (a - b).abs() < 0.5 / 255.0)
+        })
+    }
+
+    /// Converts RGB8 pixels into sRGB.
+    pub fn convert_rgb(&self, data: &mut [u8]) {
+        self.convert(data, 3, [0, 1, 2]);
+    }
+
+    /// Converts non-premultiplied RGBA8 pixels into sRGB.
+    pub fn convert_rgba(&self, data: &mut [u8]) {
+        self.convert(data, 4, [0, 1, 2]);
+    }
+
+    /// Converts non-premultiplied BGRA8 pixels into sRGB.
+    pub fn convert_bgra(&self, data: &mut [u8]) {
+        self.convert(data, 4, [2, 1, 0]);
+    }
+
+    fn convert(&self, data: &mut [u8], channels: usize, order: [usize; 3]) {
+        let encode = build_encode_table();
+        let to_index = |n: f32| {
+            let n = if n > 1.0 { 1.0 } else if n > 0.0 { n } else { 0.0 };
+            (n * (ENCODE_TABLE_SIZE - 1) as f32).round() as usize
+        };
+
+        for pixel in data.chunks_exact_mut(channels) {
+            let r = self.curves[0][pixel[order[0]] as usize];
+            let g = self.curves[1][pixel[order[1]] as usize];
+            let b = self.curves[2][pixel[order[2]] as usize];
+
+            let (r, g, b) = match self.matrix {
+                Some(ref m) => {
+                    let r = r as f64;
+                    let g = g as f64;
+                    let b = b as f64;
+                    (
+                        (m[0][0] * r + m[0][1] * g + m[0][2] * b) as f32,
+                        (m[1][0] * r + m[1][1] * g + m[1][2] * b) as f32,
+                        (m[2][0] * r + m[2][1] * g + m[2][2] * b) as f32,
+                    )
+                }
+                None => (r, g, b),
+            };
+
+            pixel[order[0]] = encode[to_index(r)];
+            pixel[order[1]] = encode[to_index(g)];
+            pixel[order[2]] = encode[to_index(b)];
+        }
+    }
+}
+
+
+fn build_table<F: Fn(f64) -> f64>(f: F) -> Vec<f32> {
+    (0..256).map(|i| f(i as f64 / 255.0) as f32).collect()
+}
+
+fn build_encode_table() -> Vec<u8> {
+    (0..ENCODE_TABLE_SIZE).map(|i| {
+        let n = linear_to_srgb(i as f64 / (ENCODE_TABLE_SIZE - 1) as f64);
+        (n * 255.0).round() as u8
+    }).collect()
+}
+
+fn srgb_to_linear(n: f64) -> f64 {
+    if n <= 0.04045 {
+        n / 12.92
+    } else {
+        ((n + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(n: f64) -> f64 {
+    if n <= 0.0031308 {
+        n * 12.92
+    } else {
+        1.055 * n.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+
+/// Converts PNG `cHRM` values into an RGB to XYZ D50 matrix.
+fn chromaticities_to_xyz_d50(values: [f64; 8]) -> Option<Matrix> {
+    let xy_to_xyz = |x: f64, y: f64| -> Option<[f64; 3]> {
+        if y <= 0.0 {
+            return None;
+        }
+
+        Some([x / y, 1.0, (1.0 - x - y) / y])
+    };
+
+    let white = xy_to_xyz(values[0], values[1])?;
+    let r = xy_to_xyz(values[2], values[3])?;
+    let g = xy_to_xyz(values[4], values[5])?;
+    let b = xy_to_xyz(values[6], values[7])?;
+
+    let primaries = [
+        [r[0], g[0], b[0]],
+        [r[1], g[1], b[1]],
+        [r[2], g[2], b[2]],
+    ];
+
+    // Scale primaries so that RGB(1, 1, 1) becomes the white point.
+    let s = mul_vector(&invert_matrix(&primaries)?, white);
+    let to_xyz = [
+        [primaries[0][0] * s[0], primaries[0][1] * s[1], primaries[0][2] * s[2]],
+        [primaries[1][0] * s[0], primaries[1][1] * s[1], primaries[1][2] * s[2]],
+        [primaries[2][0] * s[0], primaries[2][1] * s[1], primaries[2][2] * s[2]],
+    ];
+
+    // Bradford chromatic adaptation to D50.
+    let src = mul_vector(&BRADFORD, white);
+    let dst = mul_vector(&BRADFORD, D50);
+    let scale = [
+        [dst[0] / src[0], 0.0, 0.0],
+        [0.0, dst[1] / src[1], 0.0],
+        [0.0, 0.0, dst[2] / src[2]],
+    ];
+    let adapt = mul_matrix(&invert_matrix(&BRADFORD)?, &mul_matrix(&scale, &BRADFORD));
+
+    Some(mul_matrix(&adapt, &to_xyz))
+}
+
+fn mul_matrix(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut m = [[0.0; 3]; 3];
+    for y in 0..3 {
+        for x in 0..3 {
+            m[y][x] = a[y][0] * b[0][x] + a[y][1] * b[1][x] + a[y][2] * b[2][x];
+        }
+    }
+
+    m
+}
+
+fn mul_vector(m: &Matrix, v: [f64; 3]) -> [f64; 3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+fn invert_matrix(m: &Matrix) -> Option<Matrix> {
+    let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+
+    if det.abs() < std::f64::EPSILON {
+        return None;
+    }
+
+    Some([
+        [
+            (m[1][1] * m[2][2] - m[1][2] * m[2][1]) / det,
+            (m[0][2] * m[2][1] - m[0][1] * m[2][2]) / det,
+            (m[0][1] * m[1][2] - m[0][2] * m[1][1]) / det,
+        ],
+        [
+            (m[1][2] * m[2][0] - m[1][0] * m[2][2]) / det,
+            (m[0][0] * m[2][2] - m[0][2] * m[2][0]) / det,
+            (m[0][2] * m[1][0] - m[0][0] * m[1][2]) / det,
+        ],
+        [
+            (m[1][0] * m[2][1] - m[1][1] * m[2][0]) / det,
+            (m[0][1] * m[2][0] - m[0][0] * m[2][1]) / det,
+            (m[0][0] * m[1][1] - m[0][1] * m[1][0]) / det,
+        ],
+    ])
+}
+
+
+fn find_icc_tag<'a>(data: &'a [u8], sig: &[u8; 4]) -> Option<&'a [u8]> {
+    let count = read_u32(data, 128)? as usize;
+    for i in 0..count {
+        let entry = 132 + i * 12;
+        if data.get(entry..entry + 4)? == sig {
+            let offset = read_u32(data, entry + 4)? as usize;
+            let len = read_u32(data, entry + 8)? as usize;
+            return data.get(offset..offset.checked_add(len)?);
+        }
+    }
+
+    None
+}
+
+fn parse_xyz_tag(data: &[u8], sig: &[u8; 4]) -> Option<[f64; 3]> {
+    let tag = find_icc_tag(data, sig)?;
+    if tag.get(0..4)? != b"XYZ " {
+        return None;
+    }
+
+    Some([read_s15_fixed16(tag, 8)?, read_s15_fixed16(tag, 12)?, read_s15_fixed16(tag, 16)?])
+}
+
+/// Parses a `curv` or `para` tag into a decoding table.
+fn parse_curve_tag(data: &[u8], sig: &[u8; 4]) -> Option<Vec<f32>> {
+    let tag = find_icc_tag(data, sig)?;
+    match tag.get(0..4)? {
+        b"curv" => {
+            // Check the table size first, since `count` is not trusted.
+            let count = read_u32(tag, 8)? as usize;
+            if tag.len().saturating_sub(12) / 2 < count {
+                return None;
+            }
+
+            match count {
+                0 => Some(build_table(|x| x)),
+                1 => {
+                    let gamma = read_u16(tag, 12)? as f64 / 256.0;
+                    Some(build_table(|x| x.powf(gamma)))
+                }
+                _ => {
+                    let mut values = Vec::with_capacity(count);
+                    for i in 0..count {
+                        values.push(read_u16(tag, 12 + i * 2)? as f64 / 65535.0);
+                    }
+
+                    Some(build_table(|x| {
+                        let pos = x * (count - 1) as f64;
+                        let idx = (pos.floor() as usize).min(count - 2);
+                        let t = pos - idx as f64;
+                        values[idx] + (values[idx + 1] - values[idx]) * t
+                    }))
+                }
+            }
+        }
+        b"para" => {
+            let func = read_u16(tag, 8)?;
+            let params_count = match func {
+                0 => 1,
+                1 => 3,
+                2 => 4,
+                3 => 5,
+                4 => 7,
+                _ => return None,
+            };
+
+            let mut p = [0.0; 7];
+            for (i, n) in p.iter_mut().take(params_count).enumerate() {
+                *n = read_s15_fixed16(tag, 12 + i * 4)?;
+            }
+
+            let (g, a, b, c, d, e, f) = (p[0], p[1], p[2], p[3], p[4], p[5], p[6]);
+            Some(build_table(|x| {
+                match func {
+                    0 => x.powf(g),
+                    1 => if x >= -b / a { (a * x + b).powf(g) } else { 0.0 },
+                    2 => if x >= -b / a { (a * x + b).powf(g) + c } else { c },
+                    3 => if x >= d { (a * x + b).powf(g) } else { c * x },
+                    _ => if x >= d { (a * x + b).powf(g) + e } else { c * x + f },
+                }
+            }))
+        }
+        _ => {
+            warn!("Only curve-based ICC profiles are supported.");
+            None
+        }
+    }
+}
+
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_s15_fixed16(data: &[u8], offset: usize) -> Option<f64> {
+    Some(read_u32(data, offset)? as i32 as f64 / 65536.0)
+}
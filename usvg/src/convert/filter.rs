@@ -4,10 +4,10 @@
 
 use std::collections::HashSet;
 
-use crate::svgtree;
-use crate::tree;
+use crate::{svgtree, tree};
 use super::prelude::*;
 use super::paint_server::{resolve_number, convert_units};
+use super::units;
 
 
 pub fn convert(
@@ -52,6 +52,304 @@ pub fn convert(
     Some(node.element_id().to_string())
 }
 
+/// Converts a `filter` list.
+///
+/// Consecutive filter functions are merged into a single filter.
+///
+/// Returns `None` when any of the links is invalid.
+pub fn convert_list(
+    node: svgtree::Node,
+    values: &[svgtree::FilterValue],
+    state: &State,
+    tree: &mut tree::Tree,
+) -> Option<Vec<String>> {
+    let mut ids = Vec::new();
+    let mut functions = Vec::new();
+    for value in values {
+        match *value {
+            svgtree::FilterValue::Url(ref id) => {
+                if !functions.is_empty() {
+                    ids.push(convert_functions(node, &functions, state, tree)?);
+                    functions.clear();
+                }
+
+                let link = node.document().element_by_id(id)?;
+                ids.push(convert(link, state, tree)?);
+            }
+            svgtree::FilterValue::Function(func) => {
+                functions.push(func);
+            }
+        }
+    }
+
+    if !functions.is_empty() {
+        ids.push(convert_functions(node, &functions, state, tree)?);
+    }
+
+    Some(ids)
+}
+
+/// Converts CSS filter functions into an equivalent filter.
+///
+/// Each function is converted into one filter primitive,
+/// using the previous primitive result as an input.
+fn convert_functions(
+    node: svgtree::Node,
+    functions: &[svgtree::FilterFunction],
+    state: &State,
+    tree: &mut tree::Tree,
+) -> Option<String> {
+    use svgtree::FilterFunction;
+
+    let mut children: Vec<tree::FilterPrimitive> = Vec::new();
+    for func in functions {
+        let input = match children.last() {
+            Some(prev) => tree::FilterInput::Reference(prev.result.clone()),
+            None => tree::FilterInput::SourceGraphic,
+        };
+
+        let kind = match *func {
+            FilterFunction::Blur(std_dev) => {
+                let std_dev = convert_function_length(std_dev, node, AId::StdDeviation, state);
+                tree::FilterKind::FeGaussianBlur(tree::FeGaussianBlur {
+                    input,
+                    std_dev_x: std_dev.into(),
+                    std_dev_y: std_dev.into(),
+                })
+            }
+            FilterFunction::DropShadow { color, dx, dy, std_dev } => {
+                let std_dev = convert_function_length(std_dev, node, AId::StdDeviation, state);
+                tree::FilterKind::FeDropShadow(tree::FeDropShadow {
+                    input,
+                    dx: convert_function_length(dx, node, AId::Dx, state),
+                    dy: convert_function_length(dy, node, AId::Dy, state),
+                    std_dev_x: std_dev.into(),
+                    std_dev_y: std_dev.into(),
+                    color: color.unwrap_or_else(|| {
+                        node.find_attribute(AId::Color).unwrap_or_else(tree::Color::black)
+                    }),
+                    opacity: tree::Opacity::default(),
+                })
+            }
+            FilterFunction::Brightness(amount) => {
+                create_linear_transfer(input, amount, 0.0)
+            }
+            FilterFunction::Contrast(amount) => {
+                create_linear_transfer(input, amount, -0.5 * amount + 0.5)
+            }
+            FilterFunction::Grayscale(amount) => {
+                let a = 1.0 - amount;
+                create_color_matrix(input, vec![
+                    0.2126 + 0.7874 * a, 0.7152 - 0.7152 * a, 0.0722 - 0.0722 * a, 0.0, 0.0,
+                    0.2126 - 0.2126 * a, 0.7152 + 0.2848 * a, 0.0722 - 0.0722 * a, 0.0, 0.0,
+                    0.2126 - 0.2126 * a, 0.7152 - 0.7152 * a, 0.0722 + 0.9278 * a, 0.0, 0.0,
+                    0.0, 0.0, 0.0, 1.0, 0.0,
+                ])
+            }
+            FilterFunction::HueRotate(angle) => {
+                tree::FilterKind::FeColorMatrix(tree::FeColorMatrix {
+                    input,
+                    kind: tree::FeColorMatrixKind::HueRotate(angle),
+                })
+            }
+            FilterFunction::Invert(amount) => {
+                let func = tree::TransferFunction::Table(vec![amount, 1.0 - amount]);
+                tree::FilterKind::FeComponentTransfer(tree::FeComponentTransfer {
+                    input,
+                    func_r: func.clone(),
+                    func_g: func.clone(),
+                    func_b: func,
+                    func_a: tree::TransferFunction::Identity,
+                })
+            }
+            FilterFunction::Opacity(amount) => {
+                tree::FilterKind::FeComponentTransfer(tree::FeComponentTransfer {
+                    input,
+                    func_r: tree::TransferFunction::Identity,
+                    func_g: tree::TransferFunction::Identity,
+                    func_b: tree::TransferFunction::Identity,
+                    func_a: tree::TransferFunction::Table(vec![0.0, amount]),
+                })
+            }
+            FilterFunction::Saturate(amount) => {
+                // Unlike `feColorMatrix`, values bigger than 1 are allowed,
+                // so we cannot use `FeColorMatrixKind::Saturate`.
+                let a = amount;
+                create_color_matrix(input, vec![
+                    0.213 + 0.787 * a, 0.715 - 0.715 * a, 0.072 - 0.072 * a, 0.0, 0.0,
+                    0.213 - 0.213 * a, 0.715 + 0.285 * a, 0.072 - 0.072 * a, 0.0, 0.0,
+                    0.213 - 0.213 * a, 0.715 - 0.715 * a, 0.072 + 0.928 * a, 0.0, 0.0,
+                    0.0, 0.0, 0.0, 1.0, 0.0,
+                ])
+            }
+            FilterFunction::Sepia(amount) => {
+                let a = 1.0 - amount;
+                create_color_matrix(input, vec![
+                    0.393 + 0.607 * a, 0.769 - 0.769 * a, 0.189 - 0.189 * a, 0.0, 0.0,
+                    0.349 - 0.349 * a, 0.686 + 0.314 * a, 0.168 - 0.168 * a, 0.0, 0.0,
+                    0.272 - 0.272 * a, 0.534 - 0.534 * a, 0.131 + 0.869 * a, 0.0, 0.0,
+                    0.0, 0.0, 0.0, 1.0, 0.0,
+                ])
+            }
+        };
+
+        children.push(tree::FilterPrimitive {
+            x: None,
+            y: None,
+            width: None,
+            height: None,
+            color_interpolation: tree::ColorInterpolation::SRGB,
+            result: format!("result{}", children.len() + 1),
+            kind,
+        });
+    }
+
+    // Filter functions, unlike the `filter` element, do not have a filter region.
+    // We start with the default one and expand it by the blur and offset extents
+    // once the element bbox is known. See `resolve_functions_regions`.
+    let rect = Rect::new(-0.1, -0.1, 1.2, 1.2)?;
+
+    let id = state.id_generator.gen("filter", node.document());
+    state.function_filters.borrow_mut().insert(id.clone());
+    tree.append_to_defs(
+        tree::NodeKind::Filter(tree::Filter {
+            id: id.clone(),
+            units: tree::Units::ObjectBoundingBox,
+            primitive_units: tree::Units::UserSpaceOnUse,
+            rect,
+            children,
+        })
+    );
+
+    Some(id)
+}
+
+/// Expands filter functions regions to include blur and offset extents.
+///
+/// Unlike the default region, those extents are in user units,
+/// so we have to wait until elements are converted and their bbox is known.
+pub fn resolve_functions_regions(
+    ids: &HashSet<String>,
+    tree: &mut tree::Tree,
+) {
+    let mut regions = Vec::new();
+    for node in tree.root().descendants() {
+        if let tree::NodeKind::Group(ref g) = *node.borrow() {
+            for id in &g.filters {
+                if !ids.contains(id) {
+                    continue;
+                }
+
+                let filter_node = match tree.defs_by_id(id) {
+                    Some(v) => v,
+                    None => continue,
+                };
+                let (left, top, right, bottom) = match *filter_node.borrow() {
+                    tree::NodeKind::Filter(ref filter) => calc_functions_extents(&filter.children),
+                    _ => continue,
+                };
+
+                if left.is_fuzzy_zero() && top.is_fuzzy_zero()
+                    && right.is_fuzzy_zero() && bottom.is_fuzzy_zero()
+                {
+                    continue;
+                }
+
+                let bbox = match super::calc_fill_bbox(&node, tree::Transform::default()) {
+                    Some(v) => v,
+                    None => continue,
+                };
+
+                let rect = Rect::new(
+                    bbox.x() - bbox.width() * 0.1 - left,
+                    bbox.y() - bbox.height() * 0.1 - top,
+                    bbox.width() * 1.2 + left + right,
+                    bbox.height() * 1.2 + top + bottom,
+                );
+
+                if let Some(rect) = rect {
+                    regions.push((filter_node, rect));
+                }
+            }
+        }
+    }
+
+    for (mut filter_node, rect) in regions {
+        if let tree::NodeKind::Filter(ref mut filter) = *filter_node.borrow_mut() {
+            filter.units = tree::Units::UserSpaceOnUse;
+            filter.rect = rect;
+        }
+    }
+}
+
+/// Calculates how far filter primitives can draw outside the source graphic.
+///
+/// Returns left, top, right and bottom extents.
+fn calc_functions_extents(children: &[tree::FilterPrimitive]) -> (f64, f64, f64, f64) {
+    // A blur is practically invisible after three standard deviations.
+    let blur = |std_dev_x: tree::PositiveNumber, std_dev_y: tree::PositiveNumber| {
+        (std_dev_x.value() * 3.0, std_dev_y.value() * 3.0)
+    };
+
+    let (mut left, mut top, mut right, mut bottom) = (0.0f64, 0.0f64, 0.0f64, 0.0f64);
+    for fe in children {
+        match fe.kind {
+            tree::FilterKind::FeGaussianBlur(ref fe) => {
+                let (bx, by) = blur(fe.std_dev_x, fe.std_dev_y);
+                left += bx;
+                right += bx;
+                top += by;
+                bottom += by;
+            }
+            tree::FilterKind::FeDropShadow(ref fe) => {
+                // A shadow is drawn under the source graphic, so the extents can only grow.
+                let (bx, by) = blur(fe.std_dev_x, fe.std_dev_y);
+                left = left.max(left - fe.dx + bx);
+                right = right.max(right + fe.dx + bx);
+                top = top.max(top - fe.dy + by);
+                bottom = bottom.max(bottom + fe.dy + by);
+            }
+            _ => {}
+        }
+    }
+
+    (left, top, right, bottom)
+}
+
+fn convert_function_length(
+    length: Length,
+    node: svgtree::Node,
+    aid: AId,
+    state: &State,
+) -> f64 {
+    units::convert_length(length, node, aid, tree::Units::UserSpaceOnUse, state)
+}
+
+fn create_linear_transfer(
+    input: tree::FilterInput,
+    slope: f64,
+    intercept: f64,
+) -> tree::FilterKind {
+    let func = tree::TransferFunction::Linear { slope, intercept };
+    tree::FilterKind::FeComponentTransfer(tree::FeComponentTransfer {
+        input,
+        func_r: func.clone(),
+        func_g: func.clone(),
+        func_b: func,
+        func_a: tree::TransferFunction::Identity,
+    })
+}
+
+fn create_color_matrix(
+    input: tree::FilterInput,
+    matrix: Vec<f64>,
+) -> tree::FilterKind {
+    tree::FilterKind::FeColorMatrix(tree::FeColorMatrix {
+        input,
+        kind: tree::FeColorMatrixKind::Matrix(matrix),
+    })
+}
+
 fn find_filter_with_children(
     node: svgtree::Node,
 ) -> Option<svgtree::Node> {
@@ -96,6 +394,7 @@ fn collect_children(
             EId::FeOffset => convert_fe_offset(child, &primitives, state),
             EId::FeBlend => convert_fe_blend(child, &primitives),
             EId::FeFlood => convert_fe_flood(child),
+            EId::FeDropShadow => convert_fe_drop_shadow(child, &primitives, state),
             EId::FeComposite => convert_fe_composite(child, &primitives),
             EId::FeMerge => convert_fe_merge(child, &primitives),
             EId::FeTile => convert_fe_tile(child, &primitives),
@@ -146,7 +445,20 @@ fn convert_fe_gaussian_blur(
     fe: svgtree::Node,
     primitives: &[tree::FilterPrimitive],
 ) -> tree::FilterKind {
-    let text = fe.attribute::<&str>(AId::StdDeviation).unwrap_or("0 0");
+    let (std_dev_x, std_dev_y) = convert_std_dev(fe, "0 0");
+
+    tree::FilterKind::FeGaussianBlur(tree::FeGaussianBlur {
+        input: resolve_input(fe, AId::In, primitives),
+        std_dev_x,
+        std_dev_y,
+    })
+}
+
+fn convert_std_dev(
+    fe: svgtree::Node,
+    default: &str,
+) -> (tree::PositiveNumber, tree::PositiveNumber) {
+    let text = fe.attribute::<&str>(AId::StdDeviation).unwrap_or(default);
     let mut parser = svgtypes::NumberListParser::from(text);
 
     let n1 = parser.next().and_then(|n| n.ok());
@@ -164,11 +476,7 @@ fn convert_fe_gaussian_blur(
     if std_dev_x.is_sign_negative() { std_dev_x = 0.0; }
     if std_dev_y.is_sign_negative() { std_dev_y = 0.0; }
 
-    tree::FilterKind::FeGaussianBlur(tree::FeGaussianBlur {
-        input: resolve_input(fe, AId::In, primitives),
-        std_dev_x: std_dev_x.into(),
-        std_dev_y: std_dev_y.into(),
-    })
+    (std_dev_x.into(), std_dev_y.into())
 }
 
 fn convert_fe_offset(
@@ -216,6 +524,24 @@ fn convert_fe_flood(
     })
 }
 
+fn convert_fe_drop_shadow(
+    fe: svgtree::Node,
+    primitives: &[tree::FilterPrimitive],
+    state: &State,
+) -> tree::FilterKind {
+    let (std_dev_x, std_dev_y) = convert_std_dev(fe, "2 2");
+
+    tree::FilterKind::FeDropShadow(tree::FeDropShadow {
+        input: resolve_input(fe, AId::In, primitives),
+        dx: fe.convert_user_length(AId::Dx, state, Length::new_number(2.0)),
+        dy: fe.convert_user_length(AId::Dy, state, Length::new_number(2.0)),
+        std_dev_x,
+        std_dev_y,
+        color: fe.attribute(AId::FloodColor).unwrap_or_else(tree::Color::black),
+        opacity: fe.attribute(AId::FloodOpacity).unwrap_or_default(),
+    })
+}
+
 fn convert_fe_composite(
     fe: svgtree::Node,
     primitives: &[tree::FilterPrimitive],
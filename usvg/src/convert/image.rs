@@ -156,6 +156,11 @@ pub fn load_sub_svg(data: &[u8], opt: &Options) -> Option<tree::ImageKind> {
         text_rendering: opt.text_rendering,
         image_rendering: opt.image_rendering,
         keep_named_groups: false,
+        size_fallback: opt.size_fallback,
+        viewport_size: opt.viewport_size,
+        load_external_stylesheets: opt.load_external_stylesheets,
+        load_external_use: opt.load_external_use,
+        user_stylesheet: None,
         #[cfg(feature = "text")]
         fontdb: opt.fontdb.clone(),
     };
@@ -12,12 +12,15 @@ use super::{prelude::*, use_node};
 pub fn is_valid(
     node: svgtree::Node,
 ) -> bool {
-    // `marker-*` attributes can only be set on `path`, `line`, `polyline` and `polygon`.
+    // `marker-*` attributes can only be set on shapes.
     match try_opt_or!(node.tag_name(), false) {
           EId::Path
         | EId::Line
         | EId::Polyline
-        | EId::Polygon => {}
+        | EId::Polygon
+        | EId::Rect
+        | EId::Circle
+        | EId::Ellipse => {}
         _ => return false,
     }
 
@@ -72,6 +75,7 @@ enum MarkerKind {
 
 enum MarkerOrientation {
     Auto,
+    AutoStartReverse,
     Angle(f64),
 }
 
@@ -134,7 +138,12 @@ fn resolve(
         let mut ts = tree::Transform::new_translate(x, y);
 
         let angle = match convert_orientation(marker_node) {
-            MarkerOrientation::Auto => calc_vertex_angle(path, idx),
+            MarkerOrientation::AutoStartReverse if matches!(marker_kind, MarkerKind::Start) => {
+                (calc_vertex_angle(path, idx) + 180.0) % 360.0
+            }
+            MarkerOrientation::Auto | MarkerOrientation::AutoStartReverse => {
+                calc_vertex_angle(path, idx)
+            }
             MarkerOrientation::Angle(angle) => angle,
         };
 
@@ -471,6 +480,8 @@ fn convert_orientation(
 
     if node.attribute(AId::Orient) == Some("auto") {
         MarkerOrientation::Auto
+    } else if node.attribute(AId::Orient) == Some("auto-start-reverse") {
+        MarkerOrientation::AutoStartReverse
     } else {
         match node.attribute::<Angle>(AId::Orient) {
             Some(angle) => {
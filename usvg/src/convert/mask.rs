@@ -53,6 +53,7 @@ pub fn convert(
         id: node.element_id().to_string(),
         units,
         content_units,
+        kind: node.attribute(AId::MaskType).unwrap_or_default(),
         rect,
         mask,
     }));
@@ -68,3 +69,38 @@ pub fn convert(
     }
 }
 
+
+/// Applies the masked element `mask-mode` to a converted mask.
+///
+/// `alpha` and `luminance` override the mask's own `mask-type`,
+/// so when they differ, a copy of the mask is used.
+pub fn apply_mode(
+    node: svgtree::Node,
+    id: String,
+    state: &State,
+    tree: &mut tree::Tree,
+) -> String {
+    let kind = match node.attribute(AId::MaskMode) {
+        Some("alpha") => tree::MaskType::Alpha,
+        Some("luminance") => tree::MaskType::Luminance,
+        // `match-source` uses the `mask-type`.
+        _ => return id,
+    };
+
+    let mask = try_opt_or!(tree.defs_by_id(&id), id);
+    match *mask.borrow() {
+        tree::NodeKind::Mask(ref mask) if mask.kind != kind => {}
+        _ => return id,
+    }
+
+    // The original mask can be used by other elements, so we cannot modify it.
+    let mut new_mask = mask.clone().make_deep_copy();
+    let new_id = state.id_generator.gen("mask", node.document());
+    if let tree::NodeKind::Mask(ref mut mask) = *new_mask.borrow_mut() {
+        mask.id = new_id.clone();
+        mask.kind = kind;
+    }
+
+    tree.defs().append(new_mask);
+    new_id
+}
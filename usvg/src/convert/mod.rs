@@ -2,9 +2,12 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
 use svgtypes::Length;
 
-use crate::{svgtree, tree, tree::prelude::*, Error};
+use crate::{svgtree, tree, tree::prelude::*, utils, Error, SizeFallback};
 
 mod clip;
 mod filter;
@@ -32,10 +35,45 @@ use self::prelude::*;
 pub struct State<'a> {
     parent_clip_path: Option<svgtree::Node<'a>>,
     parent_marker: Option<svgtree::Node<'a>>,
+    /// Fill and stroke of an element that references a marker or of a `use` element.
+    ///
+    /// Used to resolve `context-fill` and `context-stroke`.
+    context_element: Option<(Option<tree::Fill>, Option<tree::Stroke>)>,
     fe_image_link: bool,
     size: Size,
     view_box: Rect,
     opt: &'a Options,
+    id_generator: &'a IdGenerator,
+    /// Ids of filters created from CSS filter functions.
+    function_filters: &'a RefCell<HashSet<String>>,
+    /// SVG fonts defined in the document.
+    #[cfg(feature = "text")]
+    svg_fonts: &'a [text::SvgFont],
+}
+
+
+/// Generates free ids for elements created during conversion, like `filter1`.
+///
+/// Keeps a running counter for each prefix, so the document is not rescanned
+/// for each new id.
+#[derive(Default)]
+pub struct IdGenerator {
+    counters: RefCell<HashMap<&'static str, usize>>,
+}
+
+impl IdGenerator {
+    pub fn gen(&self, prefix: &'static str, doc: &svgtree::Document) -> String {
+        let mut counters = self.counters.borrow_mut();
+        let idx = counters.entry(prefix).or_insert(0);
+        loop {
+            *idx += 1;
+            let id = format!("{}{}", prefix, idx);
+            // Generated ids never repeat, so only the document ids have to be checked.
+            if doc.element_by_id(&id).is_none() {
+                return id;
+            }
+        }
+    }
 }
 
 
@@ -50,7 +88,13 @@ pub fn convert_doc(
     opt: &Options,
 ) -> Result<tree::Tree, Error> {
     let svg = svg_doc.root_element();
-    let size = resolve_svg_size(&svg, opt)?;
+    let svg_size = resolve_svg_size(&svg, opt)?;
+    let size = match svg_size {
+        SvgSize::Resolved(size) => size,
+        // The real size will be set after conversion.
+        SvgSize::ContentBBox => Size::new(100.0, 100.0).unwrap(),
+    };
+
     let view_box = tree::ViewBox {
         rect: svg.get_viewbox().unwrap_or(size.to_rect(0.0, 0.0)),
         aspect: svg.attribute(AId::PreserveAspectRatio).unwrap_or_default(),
@@ -59,78 +103,160 @@ pub fn convert_doc(
     let svg_kind = tree::Svg { size, view_box };
     let mut tree = tree::Tree::create(svg_kind);
 
-    if !svg.is_visible_element(opt) {
-        return Ok(tree);
+    if svg.is_visible_element(opt) {
+        #[cfg(feature = "text")]
+        let svg_fonts = text::collect_svg_fonts(svg_doc);
+        let id_generator = IdGenerator::default();
+        let function_filters = RefCell::new(HashSet::new());
+
+        let state = State {
+            parent_clip_path: None,
+            parent_marker: None,
+            context_element: None,
+            fe_image_link: false,
+            size,
+            view_box: view_box.rect,
+            opt: &opt,
+            id_generator: &id_generator,
+            function_filters: &function_filters,
+            #[cfg(feature = "text")]
+            svg_fonts: &svg_fonts,
+        };
+
+        convert_children(svg_doc.root(), &state, &mut tree.root(), &mut tree);
+
+        link_fe_image(svg_doc, &state, &mut tree);
+        filter::resolve_functions_regions(&function_filters.borrow(), &mut tree);
+        remove_empty_groups(&mut tree);
+        ungroup_groups(opt, &mut tree);
+        remove_unused_defs(&mut tree);
+    }
+
+    if let SvgSize::ContentBBox = svg_size {
+        resolve_svg_size_from_bbox(&svg, opt, &mut tree)?;
     }
 
+    Ok(tree)
+}
+
+enum SvgSize {
+    Resolved(Size),
+    /// The size should be detected from the content bounding box.
+    ContentBBox,
+}
+
+fn resolve_svg_size(
+    svg: &svgtree::Node,
+    opt: &Options,
+) -> Result<SvgSize, Error> {
+    let def = Length::new(100.0, Unit::Percent);
+    let width: Length = svg.attribute(AId::Width).unwrap_or(def);
+    let height: Length = svg.attribute(AId::Height).unwrap_or(def);
+
+    if let Some(vbox) = svg.get_viewbox() {
+        return resolve_svg_size_with_viewbox(svg, vbox, opt).map(SvgSize::Resolved);
+    }
+
+    if width.unit == Unit::Percent || height.unit == Unit::Percent {
+        // Relative lengths cannot be resolved without a `viewBox`.
+        return match opt.size_fallback {
+            SizeFallback::ContentBBox => Ok(SvgSize::ContentBBox),
+            SizeFallback::Fixed(size) => {
+                let vbox = size.to_rect(0.0, 0.0);
+                resolve_svg_size_with_viewbox(svg, vbox, opt).map(SvgSize::Resolved)
+            }
+            SizeFallback::Error => Err(Error::InvalidSize),
+        };
+    }
+
+    let id_generator = IdGenerator::default();
+    let function_filters = RefCell::new(HashSet::new());
     let state = State {
         parent_clip_path: None,
         parent_marker: None,
+        context_element: None,
         fe_image_link: false,
-        size,
-        view_box: view_box.rect,
-        opt: &opt,
+        size: Size::new(100.0, 100.0).unwrap(),
+        view_box: Rect::new(0.0, 0.0, 100.0, 100.0).unwrap(),
+        opt,
+        id_generator: &id_generator,
+        function_filters: &function_filters,
+        #[cfg(feature = "text")]
+        svg_fonts: &[],
     };
 
-    convert_children(svg_doc.root(), &state, &mut tree.root(), &mut tree);
-
-    link_fe_image(svg_doc, &state, &mut tree);
-    remove_empty_groups(&mut tree);
-    ungroup_groups(opt, &mut tree);
-    remove_unused_defs(&mut tree);
+    let size = Size::new(
+        svg.convert_user_length(AId::Width, &state, def),
+        svg.convert_user_length(AId::Height, &state, def),
+    );
 
-    Ok(tree)
+    size.map(SvgSize::Resolved).ok_or_else(|| Error::InvalidSize)
 }
 
-fn resolve_svg_size(
+fn resolve_svg_size_with_viewbox(
     svg: &svgtree::Node,
+    vbox: Rect,
     opt: &Options,
 ) -> Result<Size, Error> {
-    let mut state = State {
+    let id_generator = IdGenerator::default();
+    let function_filters = RefCell::new(HashSet::new());
+    let state = State {
         parent_clip_path: None,
         parent_marker: None,
+        context_element: None,
         fe_image_link: false,
         size: Size::new(100.0, 100.0).unwrap(),
-        view_box: Rect::new(0.0, 0.0, 100.0, 100.0).unwrap(),
+        view_box: vbox,
         opt,
+        id_generator: &id_generator,
+        function_filters: &function_filters,
+        #[cfg(feature = "text")]
+        svg_fonts: &[],
     };
 
     let def = Length::new(100.0, Unit::Percent);
     let width: Length = svg.attribute(AId::Width).unwrap_or(def);
     let height: Length = svg.attribute(AId::Height).unwrap_or(def);
 
-    let view_box = svg.get_viewbox();
+    let w = if width.unit == Unit::Percent {
+        vbox.width() * (width.num / 100.0)
+    } else {
+        svg.convert_user_length(AId::Width, &state, def)
+    };
 
-    if (width.unit == Unit::Percent || height.unit == Unit::Percent) && view_box.is_none() {
-        // TODO: it this case we should detect the bounding box of all elements,
-        //       which is currently impossible
-        return Err(Error::InvalidSize);
-    }
+    let h = if height.unit == Unit::Percent {
+        vbox.height() * (height.num / 100.0)
+    } else {
+        svg.convert_user_length(AId::Height, &state, def)
+    };
 
-    let size = if let Some(vbox) = view_box {
-        state.view_box = vbox;
+    Size::new(w, h).ok_or_else(|| Error::InvalidSize)
+}
 
-        let w = if width.unit == Unit::Percent {
-            vbox.width() * (width.num / 100.0)
-        } else {
-            svg.convert_user_length(AId::Width, &state, def)
-        };
+/// Sets the tree size and viewbox using the content bounding box.
+///
+/// The bounding box is used as a `viewBox` and relative `width`/`height`
+/// are resolved against it.
+fn resolve_svg_size_from_bbox(
+    svg: &svgtree::Node,
+    opt: &Options,
+    tree: &mut tree::Tree,
+) -> Result<(), Error> {
+    let bbox = tree.root().calculate_bbox().ok_or(Error::InvalidSize)?;
 
-        let h = if height.unit == Unit::Percent {
-            vbox.height() * (height.num / 100.0)
-        } else {
-            svg.convert_user_length(AId::Height, &state, def)
-        };
+    // A tree without renderable elements will return an initial bbox.
+    if bbox.fuzzy_eq(&Rect::new_bbox()) {
+        return Err(Error::InvalidSize);
+    }
 
-        Size::new(w, h)
-    } else {
-        Size::new(
-            svg.convert_user_length(AId::Width, &state, def),
-            svg.convert_user_length(AId::Height, &state, def),
-        )
-    };
+    let size = resolve_svg_size_with_viewbox(svg, bbox, opt)?;
+
+    if let tree::NodeKind::Svg(ref mut svg_kind) = *tree.root().borrow_mut() {
+        svg_kind.size = size;
+        svg_kind.view_box.rect = bbox;
+    }
 
-    size.ok_or_else(|| Error::InvalidSize)
+    Ok(())
 }
 
 #[inline(never)]
@@ -172,11 +298,12 @@ fn convert_element(
         return;
     }
 
-    let parent = &mut match convert_group(node, state, false, parent, tree) {
-        GroupKind::Create(g) => g,
-        GroupKind::Skip => parent.clone(),
+    let (mut parent, is_new_group) = match convert_group(node, state, false, parent, tree) {
+        GroupKind::Create(g) => (g, true),
+        GroupKind::Skip => (parent.clone(), false),
         GroupKind::Ignore => return,
     };
+    let parent = &mut parent;
 
     match tag_name {
           EId::Rect
@@ -210,6 +337,10 @@ fn convert_element(
         }
         _ => {}
     }
+
+    if is_new_group && tag_name != EId::Svg {
+        apply_fill_box_origin(node, state, tree::Transform::default(), parent);
+    }
 }
 
 // `clipPath` can have only shape and `text` children.
@@ -239,11 +370,12 @@ fn convert_clip_path_elements(
             continue;
         }
 
-        let parent = &mut match convert_group(node, state, false, parent, tree) {
-            GroupKind::Create(g) => g,
-            GroupKind::Skip => parent.clone(),
+        let (mut parent, is_new_group) = match convert_group(node, state, false, parent, tree) {
+            GroupKind::Create(g) => (g, true),
+            GroupKind::Skip => (parent.clone(), false),
             GroupKind::Ignore => continue,
         };
+        let parent = &mut parent;
 
         match tag_name {
               EId::Rect
@@ -259,7 +391,7 @@ fn convert_clip_path_elements(
             EId::Text => {
                 #[cfg(feature = "text")]
                 {
-                    if !state.opt.fontdb.is_empty() {
+                    if !state.opt.fontdb.is_empty() || !state.svg_fonts.is_empty() {
                         text::convert(node, state, parent, tree);
                     }
                 }
@@ -268,6 +400,10 @@ fn convert_clip_path_elements(
                 warn!("'{}' is no a valid 'clip-path' child.", tag_name);
             }
         }
+
+        if is_new_group {
+            apply_fill_box_origin(node, state, tree::Transform::default(), parent);
+        }
     }
 }
 
@@ -295,6 +431,15 @@ fn convert_group(
         tree::Opacity::default()
     };
 
+    // A `clipPath` child cannot be blended or isolated either.
+    let (blend_mode, isolate) = if state.parent_clip_path.is_none() {
+        let blend_mode = node.attribute(AId::MixBlendMode).unwrap_or_default();
+        let isolate = node.attribute(AId::Isolation) == Some("isolate");
+        (blend_mode, isolate)
+    } else {
+        (tree::BlendMode::Normal, false)
+    };
+
     macro_rules! resolve_link {
         ($aid:expr, $f:expr) => {{
             let mut v = None;
@@ -319,22 +464,28 @@ fn convert_group(
 
     let mask = if state.parent_clip_path.is_none() {
         resolve_link!(AId::Mask, mask::convert)
+            .map(|id| mask::apply_mode(node, id, state, tree))
     } else {
         None
     };
 
 
-    let mut filter = None;
+    let mut filters = Vec::new();
     if state.parent_clip_path.is_none() {
         if let Some(link) = node.attribute::<svgtree::Node>(AId::Filter) {
-            filter = filter::convert(link, state, tree);
-
-            // If `filter` is linked to an invalid element - skip this group completely.
-            if filter.is_none() {
-                return GroupKind::Ignore;
+            match filter::convert(link, state, tree) {
+                Some(id) => filters.push(id),
+                // If `filter` is linked to an invalid element - skip this group completely.
+                None => return GroupKind::Ignore,
             }
         } else if node.attribute(AId::Filter) == Some("none") {
             // Do nothing.
+        } else if let Some(values) = node.attribute::<&[svgtree::FilterValue]>(AId::Filter) {
+            // Just like with a single link, all links in a list must be valid.
+            match filter::convert_list(node, values, state, tree) {
+                Some(ids) => filters = ids,
+                None => return GroupKind::Ignore,
+            }
         } else if node.has_attribute(AId::Filter) {
             // A filter that not a link or a filter with a link to a non existing element.
             //
@@ -353,19 +504,21 @@ fn convert_group(
     }
 
     // TODO: move to `::deref` later.
-    let filter_fill = resolve_filter_fill(node, state, filter.as_ref().map(|t| t.as_str()), tree);
-    let filter_stroke = resolve_filter_stroke(node, state, filter.as_ref().map(|t| t.as_str()), tree);
+    let filter_fill = resolve_filter_fill(node, state, &filters, tree);
+    let filter_stroke = resolve_filter_stroke(node, state, &filters, tree);
 
-    let transform: tree::Transform = node.attribute(AId::Transform).unwrap_or_default();
+    let transform = resolve_transform(node, state);
 
     let enable_background = node.attribute(AId::EnableBackground);
 
     let is_g_or_use = node.has_tag_name(EId::G) || node.has_tag_name(EId::Use);
     let required =
            opacity.value().fuzzy_ne(&1.0)
+        || blend_mode != tree::BlendMode::Normal
+        || isolate
         || clip_path.is_some()
         || mask.is_some()
-        || filter.is_some()
+        || !filters.is_empty()
         || !transform.is_default()
         || enable_background.is_some()
         || (is_g_or_use
@@ -384,9 +537,11 @@ fn convert_group(
             id,
             transform,
             opacity,
+            blend_mode,
+            isolate,
             clip_path,
             mask,
-            filter,
+            filters,
             filter_fill,
             filter_stroke,
             enable_background,
@@ -398,17 +553,119 @@ fn convert_group(
     }
 }
 
+/// Returns the element `transform` with `transform-origin` applied.
+///
+/// Origins relative to the `fill-box` require the element content,
+/// so they are ignored here and applied by `apply_fill_box_origin` later.
+fn resolve_transform(
+    node: svgtree::Node,
+    state: &State,
+) -> tree::Transform {
+    let ts: tree::Transform = node.attribute(AId::Transform).unwrap_or_default();
+    if ts.is_default() || is_fill_box(node) {
+        return ts;
+    }
+
+    let origin: svgtree::TransformOrigin = match node.attribute(AId::TransformOrigin) {
+        Some(v) => v,
+        None => return ts,
+    };
+
+    // Relative to the `view-box`, which origin is the user space origin.
+    let units = tree::Units::UserSpaceOnUse;
+    let x = units::convert_length(origin.x, node, AId::X, units, state);
+    let y = units::convert_length(origin.y, node, AId::Y, units, state);
+    utils::transform_around(ts, x, y)
+}
+
+/// Applies a `fill-box` relative `transform-origin` to an already converted element group.
+///
+/// `content_ts` is a transform between the element and its content,
+/// like the `x`/`y` offset of the `use` element.
+fn apply_fill_box_origin(
+    node: svgtree::Node,
+    state: &State,
+    content_ts: tree::Transform,
+    g: &mut tree::Node,
+) {
+    if !is_fill_box(node) {
+        return;
+    }
+
+    let ts: tree::Transform = node.attribute(AId::Transform).unwrap_or_default();
+    if ts.is_default() {
+        return;
+    }
+
+    let origin: svgtree::TransformOrigin = try_opt!(node.attribute(AId::TransformOrigin));
+    let bbox = try_opt!(calc_fill_bbox(g, content_ts));
+
+    let resolve = |length: Length, aid: AId, size: f64| {
+        let n = units::convert_length(length, node, aid, tree::Units::ObjectBoundingBox, state);
+        if length.unit == Unit::Percent { n * size } else { n }
+    };
+
+    let x = bbox.x() + resolve(origin.x, AId::X, bbox.width());
+    let y = bbox.y() + resolve(origin.y, AId::Y, bbox.height());
+
+    if let tree::NodeKind::Group(ref mut g) = *g.borrow_mut() {
+        g.transform = utils::transform_around(ts, x, y);
+        g.transform.append(&content_ts);
+    }
+}
+
+fn is_fill_box(node: svgtree::Node) -> bool {
+    // There are no CSS boxes in SVG, so `content-box` is mapped to `fill-box`.
+    // `stroke-box` and `border-box` are not supported and fallback to `fill-box` too.
+    matches!(
+        node.attribute(AId::TransformBox),
+        Some("fill-box") | Some("content-box") | Some("stroke-box") | Some("border-box")
+    )
+}
+
+/// Calculates an object bounding box of the node children.
+fn calc_fill_bbox(
+    parent: &tree::Node,
+    ts: tree::Transform,
+) -> Option<Rect> {
+    let mut bbox = Rect::new_bbox();
+    for child in parent.children() {
+        let mut child_ts = ts;
+        child_ts.append(&child.transform());
+
+        let child_bbox = match *child.borrow() {
+            tree::NodeKind::Path(ref path) => {
+                path.data.bbox_with_transform(child_ts, None)
+            }
+            tree::NodeKind::Image(ref img) => {
+                tree::PathData::from_rect(img.view_box.rect).bbox_with_transform(child_ts, None)
+            }
+            tree::NodeKind::Group(_) => {
+                calc_fill_bbox(&child, child_ts)
+            }
+            _ => None,
+        };
+
+        if let Some(child_bbox) = child_bbox {
+            bbox = bbox.expand(child_bbox);
+        }
+    }
+
+    if bbox.fuzzy_ne(&Rect::new_bbox()) {
+        Some(bbox)
+    } else {
+        None
+    }
+}
+
 fn resolve_filter_fill(
     node: svgtree::Node,
     state: &State,
-    filter_id: Option<&str>,
+    filters: &[String],
     tree: &mut tree::Tree,
 ) -> Option<tree::Paint> {
-    let filter_node = tree.defs_by_id(filter_id?)?;
-    if let tree::NodeKind::Filter(ref filter) = *filter_node.borrow() {
-        if !filter.children.iter().any(|c| c.kind.has_input(&tree::FilterInput::FillPaint)) {
-            return None;
-        }
+    if !has_filter_input(filters, &tree::FilterInput::FillPaint, tree) {
+        return None;
     }
 
     let stroke = style::resolve_fill(node, true, state, tree)?;
@@ -418,20 +675,36 @@ fn resolve_filter_fill(
 fn resolve_filter_stroke(
     node: svgtree::Node,
     state: &State,
-    filter_id: Option<&str>,
+    filters: &[String],
     tree: &mut tree::Tree,
 ) -> Option<tree::Paint> {
-    let filter_node = tree.defs_by_id(filter_id?)?;
-    if let tree::NodeKind::Filter(ref filter) = *filter_node.borrow() {
-        if !filter.children.iter().any(|c| c.kind.has_input(&tree::FilterInput::StrokePaint)) {
-            return None;
-        }
+    if !has_filter_input(filters, &tree::FilterInput::StrokePaint, tree) {
+        return None;
     }
 
     let stroke = style::resolve_stroke(node, true, state, tree)?;
     Some(stroke.paint)
 }
 
+/// Checks that any of the filters has a specific input.
+fn has_filter_input(
+    filters: &[String],
+    input: &tree::FilterInput,
+    tree: &tree::Tree,
+) -> bool {
+    for id in filters {
+        if let Some(filter_node) = tree.defs_by_id(id) {
+            if let tree::NodeKind::Filter(ref filter) = *filter_node.borrow() {
+                if filter.children.iter().any(|c| c.kind.has_input(input)) {
+                    return true;
+                }
+            }
+        }
+    }
+
+    false
+}
+
 fn remove_empty_groups(tree: &mut tree::Tree) {
     fn rm(parent: tree::Node) -> bool {
         let mut changed = false;
@@ -449,7 +722,7 @@ fn remove_empty_groups(tree: &mut tree::Tree) {
                 //   <feFlood flood-color="green"/>
                 // </filter>
                 // <g filter="url(#filter1)"/>
-                g.filter.is_none()
+                g.filters.is_empty()
             } else {
                 false
             };
@@ -486,9 +759,11 @@ fn ungroup_groups(
                 ts = g.transform;
 
                    g.opacity.is_default()
+                && g.blend_mode == tree::BlendMode::Normal
+                && !g.isolate
                 && g.clip_path.is_none()
                 && g.mask.is_none()
-                && g.filter.is_none()
+                && g.filters.is_empty()
                 && g.enable_background.is_none()
                 && !(opt.keep_named_groups && !g.id.is_empty())
                 && !is_id_used(&parent.tree(), &g.id)
@@ -650,10 +925,17 @@ fn is_id_used(tree: &tree::Tree, id: &str) -> bool {
                 check_paint_id!(path.fill, id);
                 check_paint_id!(path.stroke, id);
             }
+            tree::NodeKind::Hatch(ref hatch) => {
+                for path in &hatch.paths {
+                    check_paint_id!(path.stroke, id);
+                }
+            }
             tree::NodeKind::Group(ref g) => {
                 check_id!(g.clip_path, id);
                 check_id!(g.mask, id);
-                check_id!(g.filter, id);
+                if g.filters.iter().any(|filter_id| filter_id == id) {
+                    return true;
+                }
                 check_paint_id2!(g.filter_fill, id);
                 check_paint_id2!(g.filter_stroke, id);
             }
@@ -701,28 +983,86 @@ fn convert_path(
         visibility = tree::Visibility::Hidden;
     }
 
+    let paint_order: tree::PaintOrder = node.find_attribute(AId::PaintOrder).unwrap_or_default();
+    let vector_effect = node.attribute(AId::VectorEffect).unwrap_or_default();
+
     let mut markers_group = None;
     if marker::is_valid(node) && visibility == tree::Visibility::Visible {
+        let mut marker_state = state.clone();
+        marker_state.context_element = Some((fill.clone(), stroke.clone()));
+
         let mut g = parent.append_kind(tree::NodeKind::Group(tree::Group::default()));
-        marker::convert(node, &path, state, &mut g, tree);
+        marker::convert(node, &path, &marker_state, &mut g, tree);
         markers_group = Some(g);
     }
 
-    parent.append_kind(tree::NodeKind::Path(tree::Path {
+    let path = tree::Path {
         id: node.element_id().to_string(),
         transform: Default::default(),
         visibility,
         fill,
         stroke,
         rendering_mode,
+        paint_order,
+        vector_effect,
         data: path,
-    }));
+    };
+
+    let mut g = match markers_group {
+        Some(g) => g,
+        None => {
+            parent.append_kind(tree::NodeKind::Path(path));
+            return;
+        }
+    };
+
+    use tree::PaintOrderKind;
+    match paint_order.order {
+        [PaintOrderKind::Markers, _, _] => {
+            // The markers group is already before `path`.
+            parent.append_kind(tree::NodeKind::Path(path));
+        }
+        [first, PaintOrderKind::Markers, last] => {
+            // Markers should be painted between the fill and the stroke,
+            // so we have to split `path` into two.
+            g.detach();
+            let id = path.id.clone();
+            let has_first = append_single_paint_path(first, id, &path, parent);
+            parent.append(g);
+            let id = if has_first { String::new() } else { path.id.clone() };
+            append_single_paint_path(last, id, &path, parent);
+        }
+        _ => {
+            // Insert markers group after `path`.
+            parent.append_kind(tree::NodeKind::Path(path));
+            g.detach();
+            parent.append(g);
+        }
+    }
+}
 
-    // Insert markers group after `path`.
-    if let Some(mut g) = markers_group {
-        g.detach();
-        parent.append(g);
+/// Appends a copy of the `path` with only the fill or only the stroke.
+///
+/// Returns `false` when the `path` doesn't have the requested paint.
+fn append_single_paint_path(
+    kind: tree::PaintOrderKind,
+    id: String,
+    path: &tree::Path,
+    parent: &mut tree::Node,
+) -> bool {
+    let mut path = tree::Path { id, ..path.clone() };
+    match kind {
+        tree::PaintOrderKind::Fill => path.stroke = None,
+        tree::PaintOrderKind::Stroke => path.fill = None,
+        tree::PaintOrderKind::Markers => return false,
+    }
+
+    if path.fill.is_none() && path.stroke.is_none() {
+        return false;
     }
+
+    parent.append_kind(tree::NodeKind::Path(path));
+    true
 }
 
 
@@ -36,7 +36,9 @@ pub fn convert(
     match node.tag_name().unwrap() {
         EId::LinearGradient => convert_linear(node, state, tree),
         EId::RadialGradient => convert_radial(node, state, tree),
+        EId::Meshgradient => convert_mesh(node, state, tree),
         EId::Pattern => convert_pattern(node, state, tree),
+        EId::Hatch => convert_hatch(node, state, tree),
         _ => unreachable!(),
     }
 }
@@ -67,6 +69,7 @@ fn convert_linear(
                 units,
                 transform,
                 spread_method: convert_spread_method(node),
+                color_interpolation: convert_color_interpolation(node),
                 stops,
             }
         })
@@ -125,6 +128,7 @@ fn convert_radial(
                 units,
                 transform,
                 spread_method,
+                color_interpolation: convert_color_interpolation(node),
                 stops,
             }
         })
@@ -136,6 +140,179 @@ fn convert_radial(
     })
 }
 
+#[inline(never)]
+fn convert_mesh(
+    node: svgtree::Node,
+    state: &State,
+    tree: &mut tree::Tree,
+) -> Option<ServerOrColor> {
+    let units = convert_units(node, AId::GradientUnits, tree::Units::ObjectBoundingBox);
+    let x = node.convert_length(AId::X, units, state, Length::zero());
+    let y = node.convert_length(AId::Y, units, state, Length::zero());
+
+    let rows = convert_mesh_rows(node, (x, y));
+    if rows.is_empty() {
+        warn!("Mesh gradient '{}' has no valid patches. Skipped.", node.element_id());
+        return None;
+    }
+
+    tree.append_to_defs(
+        tree::NodeKind::MeshGradient(tree::MeshGradient {
+            id: node.element_id().to_string(),
+            units,
+            transform: node.attribute(AId::GradientTransform).unwrap_or_default(),
+            interpolation: node.attribute(AId::Type).unwrap_or_default(),
+            rows,
+        })
+    );
+
+    Some(ServerOrColor::Server {
+        id: node.element_id().to_string(),
+        units,
+    })
+}
+
+/// Converts `meshrow` elements into a patches grid.
+///
+/// Only the first patch defines all four edges. Other patches reuse edges
+/// of the patches to the left and above, so they are defined by fewer `stop` elements,
+/// while each stop defines an edge and a color of the corner at its start.
+///
+/// Conversion stops at the first invalid patch.
+fn convert_mesh_rows(node: svgtree::Node, start: (f64, f64)) -> Vec<Vec<tree::MeshPatch>> {
+    let mut rows: Vec<Vec<tree::MeshPatch>> = Vec::new();
+    for (row_idx, row_node) in node.children().filter(|n| n.has_tag_name(EId::Meshrow)).enumerate() {
+        let mut row = Vec::new();
+        for (col_idx, patch_node) in row_node.children()
+            .filter(|n| n.has_tag_name(EId::Meshpatch)).enumerate()
+        {
+            let left = row.last().cloned();
+            let above = if row_idx > 0 {
+                match rows[row_idx - 1].get(col_idx) {
+                    Some(patch) => Some(*patch),
+                    None => break,
+                }
+            } else {
+                None
+            };
+
+            match convert_mesh_patch(patch_node, start, left, above) {
+                Some(patch) => row.push(patch),
+                None => break,
+            }
+        }
+
+        // All rows must have the same amount of patches.
+        if row.is_empty() || (row_idx > 0 && row.len() != rows[0].len()) {
+            warn!("Mesh gradient '{}' has an invalid row.", node.element_id());
+            break;
+        }
+
+        rows.push(row);
+    }
+
+    rows
+}
+
+fn convert_mesh_patch(
+    node: svgtree::Node,
+    start: (f64, f64),
+    left: Option<tree::MeshPatch>,
+    above: Option<tree::MeshPatch>,
+) -> Option<tree::MeshPatch> {
+    // Edges are in the top, right, bottom, left order.
+    let (edges, mut current): (&[usize], _) = match (left, above) {
+        (None, None) => (&[0, 1, 2, 3], start),
+        (Some(left), None) => (&[0, 1, 2], left.points[3]),
+        (None, Some(above)) => (&[1, 2, 3], above.points[6]),
+        (Some(_), Some(above)) => (&[1, 2], above.points[6]),
+    };
+
+    let stops: Vec<_> = node.children().filter(|n| n.has_tag_name(EId::Stop)).collect();
+    if stops.len() < edges.len() {
+        warn!("A mesh patch must have at least {} 'stop' elements.", edges.len());
+        return None;
+    }
+
+    let mut points = [(0.0, 0.0); 12];
+    let mut colors = [tree::Color::black(); 4];
+    let mut opacities = [tree::Opacity::default(); 4];
+    for (&edge, stop) in edges.iter().zip(stops) {
+        let path = stop.attribute::<&str>(AId::Path).unwrap_or("");
+        let [p1, p2, p3] = try_opt_warn_or!(
+            parse_mesh_edge(path, current), None,
+            "Invalid mesh patch edge: '{}'.", path
+        );
+
+        points[edge * 3 + 0] = current;
+        points[edge * 3 + 1] = p1;
+        points[edge * 3 + 2] = p2;
+        current = p3;
+
+        colors[edge] = convert_stop_color(stop);
+        opacities[edge] = stop.attribute(AId::StopOpacity).unwrap_or_default();
+    }
+
+    // Shared edges and corners are taken from adjacent patches.
+    if let Some(left) = left {
+        points[9] = left.points[6];
+        points[10] = left.points[5];
+        points[11] = left.points[4];
+        points[0] = left.points[3];
+        colors[0] = left.colors[1];
+        colors[3] = left.colors[2];
+        opacities[0] = left.opacities[1];
+        opacities[3] = left.opacities[2];
+    }
+
+    if let Some(above) = above {
+        points[0] = above.points[9];
+        points[1] = above.points[8];
+        points[2] = above.points[7];
+        points[3] = above.points[6];
+        colors[0] = above.colors[3];
+        colors[1] = above.colors[2];
+        opacities[0] = above.opacities[3];
+        opacities[1] = above.opacities[2];
+    }
+
+    Some(tree::MeshPatch { points, colors, opacities })
+}
+
+/// Parses a mesh patch edge.
+///
+/// An edge is a single `l`, `L`, `c` or `C` path segment, starting at `start`.
+/// Lines are converted into curves.
+fn parse_mesh_edge(
+    text: &str,
+    start: (f64, f64),
+) -> Option<[(f64, f64); 3]> {
+    let (sx, sy) = start;
+    let to_abs = |abs: bool, x: f64, y: f64| if abs { (x, y) } else { (sx + x, sy + y) };
+
+    // A path must start with a MoveTo.
+    let text = format!("M {} {} {}", sx, sy, text);
+    let mut parser = svgtypes::PathParser::from(text.as_str()).skip(1);
+    let edge = match parser.next()?.ok()? {
+        svgtypes::PathSegment::LineTo { abs, x, y } => {
+            let (x, y) = to_abs(abs, x, y);
+            let p1 = (sx + (x - sx) / 3.0, sy + (y - sy) / 3.0);
+            let p2 = (sx + (x - sx) * 2.0 / 3.0, sy + (y - sy) * 2.0 / 3.0);
+            [p1, p2, (x, y)]
+        }
+        svgtypes::PathSegment::CurveTo { abs, x1, y1, x2, y2, x, y } => {
+            [to_abs(abs, x1, y1), to_abs(abs, x2, y2), to_abs(abs, x, y)]
+        }
+        _ => return None,
+    };
+
+    if parser.next().is_some() {
+        return None;
+    }
+
+    Some(edge)
+}
+
 #[inline(never)]
 fn convert_pattern(
     node: svgtree::Node,
@@ -193,11 +370,63 @@ fn convert_pattern(
     })
 }
 
+fn convert_hatch(
+    node: svgtree::Node,
+    state: &State,
+    tree: &mut tree::Tree,
+) -> Option<ServerOrColor> {
+    let units = convert_units(node, AId::HatchUnits, tree::Units::ObjectBoundingBox);
+    let content_units = convert_units(node, AId::HatchContentUnits, tree::Units::UserSpaceOnUse);
+
+    let pitch = node.convert_length(AId::Pitch, units, state, Length::zero());
+    if !pitch.is_valid_length() {
+        warn!("Hatch '{}' has an invalid pitch. Skipped.", node.element_id());
+        return None;
+    }
+
+    let mut paths = Vec::new();
+    for child in node.children().filter(|n| n.has_tag_name(EId::Hatchpath)) {
+        let data = child.attribute::<tree::SharedPathData>(AId::D);
+        let has_bbox = data.as_ref().map(|d| d.has_bbox()).unwrap_or(false);
+        paths.push(tree::HatchPath {
+            offset: child.convert_length(AId::Offset, content_units, state, Length::zero()),
+            data,
+            stroke: super::style::resolve_stroke(child, has_bbox, state, tree),
+        });
+    }
+
+    if paths.is_empty() {
+        return None;
+    }
+
+    tree.append_to_defs(tree::NodeKind::Hatch(tree::Hatch {
+        id: node.element_id().to_string(),
+        units,
+        content_units,
+        transform: node.attribute(AId::Transform).unwrap_or_default(),
+        x: node.convert_length(AId::X, units, state, Length::zero()),
+        y: node.convert_length(AId::Y, units, state, Length::zero()),
+        pitch: pitch.into(),
+        rotate: node.attribute(AId::Rotate).unwrap_or(0.0),
+        paths,
+    }));
+
+    Some(ServerOrColor::Server {
+        id: node.element_id().to_string(),
+        units,
+    })
+}
+
 fn convert_spread_method(node: svgtree::Node) -> tree::SpreadMethod {
     let node = resolve_attr(node, AId::SpreadMethod);
     node.attribute(AId::SpreadMethod).unwrap_or_default()
 }
 
+fn convert_color_interpolation(node: svgtree::Node) -> tree::ColorInterpolation {
+    // Unlike `color-interpolation-filters`, the default value is `sRGB`.
+    node.find_attribute(AId::ColorInterpolation).unwrap_or(tree::ColorInterpolation::SRGB)
+}
+
 pub fn convert_units(
     node: svgtree::Node,
     name: AId,
@@ -266,21 +495,9 @@ fn convert_stops(grad: svgtree::Node) -> Vec<tree::Stop> {
             let offset = crate::utils::f64_bound(0.0, offset, 1.0);
             prev_offset = Length::new_number(offset);
 
-            let color = match stop.attribute(AId::StopColor) {
-                Some(&svgtree::AttributeValue::CurrentColor) => {
-                    stop.find_attribute(AId::Color).unwrap_or_else(tree::Color::black)
-                }
-                Some(&svgtree::AttributeValue::Color(c)) => {
-                    c
-                }
-                _ => {
-                    svgtypes::Color::black()
-                }
-            };
-
             stops.push(tree::Stop {
                 offset: offset.into(),
-                color,
+                color: convert_stop_color(stop),
                 opacity: stop.attribute(AId::StopOpacity).unwrap_or_default(),
             });
         }
@@ -367,6 +584,20 @@ fn convert_stops(grad: svgtree::Node) -> Vec<tree::Stop> {
     stops
 }
 
+fn convert_stop_color(stop: svgtree::Node) -> tree::Color {
+    match stop.attribute(AId::StopColor) {
+        Some(&svgtree::AttributeValue::CurrentColor) => {
+            stop.find_attribute(AId::Color).unwrap_or_else(tree::Color::black)
+        }
+        Some(&svgtree::AttributeValue::Color(c)) => {
+            c
+        }
+        _ => {
+            svgtypes::Color::black()
+        }
+    }
+}
+
 #[inline(never)]
 pub fn resolve_number(
     node: svgtree::Node, name: AId, units: tree::Units, state: &State, def: Length
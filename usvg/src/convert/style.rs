@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::{svgtree, tree};
+use crate::{svgtree, tree, tree::prelude::*};
 use super::{prelude::*, paint_server};
 
 
@@ -90,6 +90,14 @@ fn convert_paint(
         svgtree::AttributeValue::Color(c) => {
             Some(tree::Paint::Color(*c))
         }
+        svgtree::AttributeValue::ContextFill => {
+            let fill = state.context_element.as_ref().and_then(|(fill, _)| fill.as_ref())?;
+            convert_context_paint(&fill.paint, has_bbox, tree)
+        }
+        svgtree::AttributeValue::ContextStroke => {
+            let stroke = state.context_element.as_ref().and_then(|(_, stroke)| stroke.as_ref())?;
+            convert_context_paint(&stroke.paint, has_bbox, tree)
+        }
         svgtree::AttributeValue::Paint(func_iri, fallback) => {
             if let Some(link) = node.document().element_by_id(func_iri) {
                 let tag_name = link.tag_name().unwrap();
@@ -128,6 +136,22 @@ fn convert_paint(
     }
 }
 
+fn convert_context_paint(
+    paint: &tree::Paint,
+    has_bbox: bool,
+    tree: &tree::Tree,
+) -> Option<tree::Paint> {
+    if let tree::Paint::Link(ref id) = paint {
+        // Just like with a regular paint server link.
+        let units = tree.defs_by_id(id).and_then(|n| n.units());
+        if !has_bbox && units == Some(tree::Units::ObjectBoundingBox) {
+            return None;
+        }
+    }
+
+    Some(paint.clone())
+}
+
 fn from_fallback(
     node: svgtree::Node,
     fallback: Option<svgtypes::PaintFallback>,
@@ -54,6 +54,148 @@ impl_enum_from_str!(TextAnchor,
 );
 
 
+#[derive(Clone, Copy, PartialEq)]
+pub enum Direction {
+    Ltr,
+    Rtl,
+}
+
+impl_enum_default!(Direction, Ltr);
+
+impl_enum_from_str!(Direction,
+    "ltr"   => Direction::Ltr,
+    "rtl"   => Direction::Rtl
+);
+
+
+/// An explicit BIDI embedding or override produced by `unicode-bidi`.
+#[derive(Clone, Copy, PartialEq)]
+pub struct BidiEmbedding {
+    /// An element that has created this embedding.
+    ///
+    /// Used to detect that neighbor spans are in the same embedding.
+    pub node: svgtree::NodeId,
+    pub direction: Direction,
+    pub is_override: bool,
+}
+
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DominantBaseline {
+    Auto,
+    UseScript,
+    NoChange,
+    ResetSize,
+    Ideographic,
+    Alphabetic,
+    Hanging,
+    Mathematical,
+    Central,
+    Middle,
+    TextAfterEdge,
+    TextBeforeEdge,
+}
+
+impl_enum_default!(DominantBaseline, Auto);
+
+impl_enum_from_str!(DominantBaseline,
+    "auto"              => DominantBaseline::Auto,
+    "use-script"        => DominantBaseline::UseScript,
+    "no-change"         => DominantBaseline::NoChange,
+    "reset-size"        => DominantBaseline::ResetSize,
+    "ideographic"       => DominantBaseline::Ideographic,
+    "alphabetic"        => DominantBaseline::Alphabetic,
+    "hanging"           => DominantBaseline::Hanging,
+    "mathematical"      => DominantBaseline::Mathematical,
+    "central"           => DominantBaseline::Central,
+    "middle"            => DominantBaseline::Middle,
+    "text-after-edge"   => DominantBaseline::TextAfterEdge,
+    "text-bottom"       => DominantBaseline::TextAfterEdge,
+    "text-before-edge"  => DominantBaseline::TextBeforeEdge,
+    "text-top"          => DominantBaseline::TextBeforeEdge
+);
+
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AlignmentBaseline {
+    Auto,
+    Baseline,
+    BeforeEdge,
+    TextBeforeEdge,
+    Middle,
+    Central,
+    AfterEdge,
+    TextAfterEdge,
+    Ideographic,
+    Alphabetic,
+    Hanging,
+    Mathematical,
+}
+
+impl_enum_default!(AlignmentBaseline, Auto);
+
+impl_enum_from_str!(AlignmentBaseline,
+    "auto"              => AlignmentBaseline::Auto,
+    "baseline"          => AlignmentBaseline::Baseline,
+    "before-edge"       => AlignmentBaseline::BeforeEdge,
+    "top"               => AlignmentBaseline::BeforeEdge,
+    "text-before-edge"  => AlignmentBaseline::TextBeforeEdge,
+    "text-top"          => AlignmentBaseline::TextBeforeEdge,
+    "middle"            => AlignmentBaseline::Middle,
+    "central"           => AlignmentBaseline::Central,
+    "center"            => AlignmentBaseline::Central,
+    "after-edge"        => AlignmentBaseline::AfterEdge,
+    "bottom"            => AlignmentBaseline::AfterEdge,
+    "text-after-edge"   => AlignmentBaseline::TextAfterEdge,
+    "text-bottom"       => AlignmentBaseline::TextAfterEdge,
+    "ideographic"       => AlignmentBaseline::Ideographic,
+    "alphabetic"        => AlignmentBaseline::Alphabetic,
+    "hanging"           => AlignmentBaseline::Hanging,
+    "mathematical"      => AlignmentBaseline::Mathematical
+);
+
+
+/// An OpenType font feature.
+#[derive(Clone, Copy, PartialEq)]
+pub struct FontFeature {
+    pub tag: [u8; 4],
+    pub value: u32,
+}
+
+impl FontFeature {
+    fn new(tag: &[u8; 4], value: u32) -> Self {
+        FontFeature { tag: *tag, value }
+    }
+}
+
+
+/// A `font-variant` small caps mode.
+///
+/// Used to synthesize small caps when a font doesn't support them.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SmallCaps {
+    None,
+    /// Only lowercase letters are converted.
+    Lowercase,
+    /// All letters are converted.
+    All,
+}
+
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LengthAdjust {
+    Spacing,
+    SpacingAndGlyphs,
+}
+
+impl_enum_default!(LengthAdjust, Spacing);
+
+impl_enum_from_str!(LengthAdjust,
+    "spacing"           => LengthAdjust::Spacing,
+    "spacingAndGlyphs"  => LengthAdjust::SpacingAndGlyphs
+);
+
+
 impl crate::svgtree::EnumFromStr for fontdb::Style {
     fn enum_from_str(s: &str) -> Option<Self> {
         match s {
@@ -90,9 +232,17 @@ pub struct TextChunk {
     pub x: Option<f64>,
     pub y: Option<f64>,
     pub anchor: TextAnchor,
+    /// A paragraph direction.
+    pub direction: Direction,
     pub spans: Vec<TextSpan>,
     pub text_flow: TextFlow,
     pub text: String,
+    /// `textLength` adjustments in the order they should be applied.
+    pub text_lengths: Vec<TextLength>,
+    /// Indicates that this chunk is a line of a multi-line text.
+    ///
+    /// Trailing white space of a line hangs and doesn't affect the text alignment.
+    pub is_line: bool,
 }
 
 impl TextChunk {
@@ -108,6 +258,25 @@ impl TextChunk {
 }
 
 
+/// A `textLength` adjustment of a text chunk part.
+///
+/// Unlike spans, can overlap, since `textLength` can be set on nested elements.
+pub struct TextLength {
+    /// Start position in bytes in the text chunk.
+    pub start: usize,
+    /// End position in bytes in the text chunk.
+    pub end: usize,
+    pub length: f64,
+    pub adjust: LengthAdjust,
+}
+
+impl TextLength {
+    pub fn contains(&self, byte_offset: ByteIndex) -> bool {
+        byte_offset.value() >= self.start && byte_offset.value() < self.end
+    }
+}
+
+
 /// Spans do not overlap.
 #[derive(Clone)]
 pub struct TextSpan {
@@ -115,13 +284,21 @@ pub struct TextSpan {
     pub end: usize,
     pub fill: Option<tree::Fill>,
     pub stroke: Option<tree::Stroke>,
+    pub paint_order: tree::PaintOrder,
     pub font: fontdb_ext::Font,
     pub font_size: f64,
     pub decoration: TextDecoration,
     pub baseline_shift: f64,
+    pub dominant_baseline: DominantBaseline,
+    pub alignment_baseline: AlignmentBaseline,
     pub visibility: tree::Visibility,
     pub letter_spacing: f64,
     pub word_spacing: f64,
+    /// OpenType features in the order they should be applied.
+    pub font_features: Vec<FontFeature>,
+    pub small_caps: SmallCaps,
+    /// Explicit BIDI embeddings from the outermost to the innermost one.
+    pub bidi_embeddings: Vec<BidiEmbedding>,
 }
 
 impl TextSpan {
@@ -160,7 +337,9 @@ pub fn collect_text_chunks(
         chunks: Vec::new(),
     };
 
+    let start = text_length_start(&iter_state);
     collect_text_chunks_impl(text_node, *text_node, pos_list, state, tree, &mut iter_state);
+    push_text_length(*text_node, start, state, &mut iter_state);
 
     iter_state.chunks
 }
@@ -198,7 +377,9 @@ fn collect_text_chunks_impl(
                 iter_state.split_chunk = true;
             }
 
+            let start = text_length_start(iter_state);
             collect_text_chunks_impl(text_node, child, pos_list, state, tree, iter_state);
+            push_text_length(child, start, state, iter_state);
 
             iter_state.text_flow = TextFlow::Horizontal;
 
@@ -215,7 +396,8 @@ fn collect_text_chunks_impl(
             continue;
         }
 
-        let anchor = parent.find_attribute(AId::TextAnchor).unwrap_or_default();
+        let direction = text_node.find_attribute(AId::Direction).unwrap_or_default();
+        let anchor = resolve_text_anchor(parent, direction);
 
         // TODO: what to do when <= 0? UB?
         let font_size = units::resolve_font_size(parent, state);
@@ -234,18 +416,28 @@ fn collect_text_chunks_impl(
             }
         };
 
+        let (font_features, small_caps) = resolve_font_features(parent);
+        let kerning = resolve_kerning(parent, state);
+
         let span = TextSpan {
             start: 0,
             end: 0,
             fill: style::resolve_fill(parent, true, state, tree),
             stroke: style::resolve_stroke(parent, true, state, tree),
+            paint_order: parent.find_attribute(AId::PaintOrder).unwrap_or_default(),
             font,
             font_size,
             decoration: resolve_decoration(text_node, parent, state, tree),
             visibility: parent.find_attribute(AId::Visibility).unwrap_or_default(),
             baseline_shift: resolve_baseline_shift(parent, state),
-            letter_spacing: parent.resolve_length(AId::LetterSpacing, state, 0.0),
+            dominant_baseline: parent.find_attribute(AId::DominantBaseline).unwrap_or_default(),
+            alignment_baseline: resolve_alignment_baseline(parent),
+            letter_spacing: parent.resolve_length(AId::LetterSpacing, state, 0.0)
+                + kerning.unwrap_or(0.0),
             word_spacing: parent.resolve_length(AId::WordSpacing, state, 0.0),
+            font_features,
+            small_caps,
+            bidi_embeddings: resolve_bidi_embeddings(parent),
         };
 
         let mut is_new_span = true;
@@ -276,9 +468,12 @@ fn collect_text_chunks_impl(
                     x: pos_list[iter_state.chars_count].x,
                     y: pos_list[iter_state.chars_count].y,
                     anchor,
+                    direction,
                     spans: vec![span2],
                     text_flow: iter_state.text_flow.clone(),
                     text: c.to_string(),
+                    text_lengths: Vec::new(),
+                    is_line: false,
                 });
             } else if is_new_span {
                 // Add this span to the last text chunk.
@@ -308,6 +503,102 @@ fn collect_text_chunks_impl(
     }
 }
 
+/// Resolves `text-anchor` against the paragraph direction.
+///
+/// This way we can treat `start` as left and `end` as right later.
+fn resolve_text_anchor(node: svgtree::Node, direction: Direction) -> TextAnchor {
+    let anchor = node.find_attribute(AId::TextAnchor).unwrap_or_default();
+    match (anchor, direction) {
+        (TextAnchor::Start, Direction::Rtl) => TextAnchor::End,
+        (TextAnchor::End, Direction::Rtl) => TextAnchor::Start,
+        (anchor, _) => anchor,
+    }
+}
+
+/// Collects explicit BIDI embeddings produced by `unicode-bidi`.
+///
+/// Isolates are not supported by the BIDI algorithm implementation we are using,
+/// so they are treated as embeddings.
+fn resolve_bidi_embeddings(node: svgtree::Node) -> Vec<BidiEmbedding> {
+    let mut list = Vec::new();
+    for n in node.ancestors() {
+        let is_override = match n.attribute(AId::UnicodeBidi) {
+            Some("embed") | Some("isolate") => Some(false),
+            Some("bidi-override") | Some("isolate-override") => Some(true),
+            _ => None,
+        };
+
+        if let Some(is_override) = is_override {
+            list.push(BidiEmbedding {
+                node: n.id(),
+                direction: n.find_attribute(AId::Direction).unwrap_or_default(),
+                is_override,
+            });
+        }
+
+        if n.has_tag_name(EId::Text) {
+            break;
+        }
+    }
+
+    list.reverse();
+    list
+}
+
+/// Returns the current chunks count and the current chunk length in bytes.
+fn text_length_start(iter_state: &IterState) -> (usize, usize) {
+    (iter_state.chunks.len(), iter_state.chunk_bytes_count)
+}
+
+/// Resolves element's `textLength` and attaches it to the text chunk
+/// that contains all of the element's characters.
+///
+/// `start` must be acquired via `text_length_start` before the element processing.
+fn push_text_length(
+    node: svgtree::Node,
+    start: (usize, usize),
+    state: &State,
+    iter_state: &mut IterState,
+) {
+    if !node.has_attribute(AId::TextLength) {
+        return;
+    }
+
+    let length = node.resolve_length(AId::TextLength, state, 0.0);
+    if !length.is_valid_length() {
+        return;
+    }
+
+    let (start_chunk, start_byte) = start;
+
+    // Check if the element's text was appended to the previous chunk.
+    let prev_extended = match start_chunk.checked_sub(1) {
+        Some(idx) => iter_state.chunks[idx].text.len() > start_byte,
+        None => false,
+    };
+
+    let chunks_count = iter_state.chunks.len();
+    let (chunk_idx, start_byte) = if prev_extended && chunks_count == start_chunk {
+        (start_chunk - 1, start_byte)
+    } else if !prev_extended && chunks_count == start_chunk + 1 {
+        (start_chunk, 0)
+    } else if !prev_extended && chunks_count == start_chunk {
+        // No text.
+        return;
+    } else {
+        warn!("'textLength' on an element that contains multiple text chunks is not supported.");
+        return;
+    };
+
+    let chunk = &mut iter_state.chunks[chunk_idx];
+    chunk.text_lengths.push(TextLength {
+        start: start_byte,
+        end: chunk.text.len(),
+        length,
+        adjust: node.attribute(AId::LengthAdjust).unwrap_or_default(),
+    });
+}
+
 fn resolve_text_flow(
     node: svgtree::Node,
     state: &State,
@@ -402,6 +693,26 @@ fn resolve_font(
     // Use the default font as fallback.
     name_list.push(fontdb::Family::Name(&state.opt.font_family));
 
+    // SVG fonts defined in the document have a higher priority than the system ones
+    // with the same family name, but not than the preceding families from the list.
+    let svg_font = name_list.iter().enumerate().find_map(|(i, family)| {
+        match family {
+            fontdb::Family::Name(name) => {
+                state.svg_fonts.iter()
+                    .find(|font| font.family.eq_ignore_ascii_case(name))
+                    .map(|font| (i, font.font))
+            }
+            _ => None,
+        }
+    });
+
+    if let Some((idx, svg_font)) = svg_font {
+        name_list.truncate(idx);
+        if name_list.is_empty() {
+            return Some(svg_font);
+        }
+    }
+
     let query = fontdb::Query {
         families: &name_list,
         weight,
@@ -409,6 +720,13 @@ fn resolve_font(
         style,
     };
 
+    if let Some((_, svg_font)) = svg_font {
+        return match state.opt.fontdb.query(&query) {
+            Some(id) => state.opt.fontdb.load_font(id),
+            None => Some(svg_font),
+        };
+    }
+
     let id = try_opt_warn_or!(
         state.opt.fontdb.query(&query), None,
         "No match for '{}' font-family.", font_family
@@ -585,6 +903,7 @@ pub fn resolve_rotate_list(text_node: TextNode) -> Vec<f64> {
 pub struct TextDecorationStyle {
     pub fill: Option<tree::Fill>,
     pub stroke: Option<tree::Stroke>,
+    pub paint_order: tree::PaintOrder,
 }
 
 #[derive(Clone)]
@@ -620,6 +939,7 @@ fn resolve_decoration(
         Some(TextDecorationStyle {
             fill: style::resolve_fill(n, true, state, tree),
             stroke: style::resolve_stroke(n, true, state, tree),
+            paint_order: n.find_attribute(AId::PaintOrder).unwrap_or_default(),
         })
     };
 
@@ -697,6 +1017,130 @@ fn resolve_baseline_shift(
     shift
 }
 
+/// Resolves `alignment-baseline`.
+///
+/// Unlike `dominant-baseline`, this property is not inherited,
+/// but an element alignment affects all its children.
+/// So we are using the closest explicitly set value.
+fn resolve_alignment_baseline(node: svgtree::Node) -> AlignmentBaseline {
+    for n in node.ancestors() {
+        match n.attribute(AId::AlignmentBaseline) {
+            Some(AlignmentBaseline::Auto) | None => {}
+            Some(v) => return v,
+        }
+
+        if n.has_tag_name(EId::Text) {
+            break;
+        }
+    }
+
+    AlignmentBaseline::Auto
+}
+
+/// Resolves OpenType features from `font-variant`, `font-feature-settings` and `kerning`.
+///
+/// `font-feature-settings` has a higher priority, so it goes last.
+fn resolve_font_features(node: svgtree::Node) -> (Vec<FontFeature>, SmallCaps) {
+    let mut features = Vec::new();
+    let mut small_caps = SmallCaps::None;
+
+    let variant = node.find_attribute(AId::FontVariant).unwrap_or("normal");
+    for name in variant.split_whitespace() {
+        let list: &[&[u8; 4]] = match name {
+            "small-caps" => {
+                small_caps = SmallCaps::Lowercase;
+                &[b"smcp"]
+            }
+            "all-small-caps" => {
+                small_caps = SmallCaps::All;
+                &[b"smcp", b"c2sc"]
+            }
+            "petite-caps" => &[b"pcap"],
+            "all-petite-caps" => &[b"pcap", b"c2pc"],
+            "unicase" => &[b"unic"],
+            "titling-caps" => &[b"titl"],
+            "common-ligatures" => &[b"liga", b"clig"],
+            "discretionary-ligatures" => &[b"dlig"],
+            "historical-ligatures" => &[b"hlig"],
+            "contextual" => &[b"calt"],
+            "lining-nums" => &[b"lnum"],
+            "oldstyle-nums" => &[b"onum"],
+            "proportional-nums" => &[b"pnum"],
+            "tabular-nums" => &[b"tnum"],
+            "diagonal-fractions" => &[b"frac"],
+            "stacked-fractions" => &[b"afrc"],
+            "ordinal" => &[b"ordn"],
+            "slashed-zero" => &[b"zero"],
+            _ => &[],
+        };
+
+        features.extend(list.iter().map(|tag| FontFeature::new(tag, 1)));
+
+        // Features disabled by default.
+        let list: &[&[u8; 4]] = match name {
+            "none" => &[b"liga", b"clig", b"dlig", b"hlig", b"calt"],
+            "no-common-ligatures" => &[b"liga", b"clig"],
+            "no-discretionary-ligatures" => &[b"dlig"],
+            "no-historical-ligatures" => &[b"hlig"],
+            "no-contextual" => &[b"calt"],
+            _ => &[],
+        };
+
+        features.extend(list.iter().map(|tag| FontFeature::new(tag, 0)));
+    }
+
+    if let Some(n) = node.find_node_with_attribute(AId::Kerning) {
+        if n.attribute::<Length>(AId::Kerning).is_some() {
+            // Any length disables the automatic kerning.
+            features.push(FontFeature::new(b"kern", 0));
+        }
+    }
+
+    let settings = node.find_attribute(AId::FontFeatureSettings).unwrap_or("normal");
+    if settings != "normal" {
+        for item in settings.split(',') {
+            match parse_feature_setting(item) {
+                Some(feature) => features.push(feature),
+                None => warn!("Invalid font-feature-settings value: '{}'.", item.trim()),
+            }
+        }
+    }
+
+    (features, small_caps)
+}
+
+/// Parses a single `font-feature-settings` item.
+///
+/// Like `"tnum"`, `"ss01" 1` or `'liga' off`.
+fn parse_feature_setting(text: &str) -> Option<FontFeature> {
+    let text = text.trim();
+    let quote = text.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let text = &text[1..];
+    let end = text.find(quote)?;
+
+    let tag = text[..end].as_bytes();
+    if tag.len() != 4 || !tag.iter().all(|c| *c >= 0x20 && *c <= 0x7E) {
+        return None;
+    }
+
+    let value = match text[end + 1..].trim() {
+        "" | "on" => 1,
+        "off" => 0,
+        n => n.parse().ok()?,
+    };
+
+    Some(FontFeature::new(&[tag[0], tag[1], tag[2], tag[3]], value))
+}
+
+/// Resolves a `kerning` length.
+///
+/// Returns `None` for `auto`.
+fn resolve_kerning(node: svgtree::Node, state: &State) -> Option<f64> {
+    let n = node.find_node_with_attribute(AId::Kerning)?;
+    let length = n.attribute(AId::Kerning)?;
+    Some(units::convert_length(length, n, AId::Kerning, tree::Units::UserSpaceOnUse, state))
+}
+
 fn resolve_font_weight(node: svgtree::Node) -> fontdb::Weight {
     fn bound(min: usize, val: usize, max: usize) -> usize {
         cmp::max(min, cmp::min(max, val))
@@ -13,6 +13,11 @@ use self::convert::*;
 mod shaper;
 use self::shaper::OutlinedCluster;
 
+mod svg_font;
+pub use self::svg_font::{collect as collect_svg_fonts, SvgFont};
+
+mod wrap;
+
 
 mod private {
     use super::*;
@@ -97,12 +102,21 @@ fn text_to_paths(
     parent: &mut tree::Node,
     tree: &mut tree::Tree,
 ) -> Vec<tree::Path> {
-    let pos_list = resolve_positions_list(text_node, state);
+    let mut pos_list = resolve_positions_list(text_node, state);
     let rotate_list = resolve_rotate_list(text_node);
     let writing_mode = convert_writing_mode(text_node);
-    let mut text_ts = tree::Transform::default();
 
-    let mut chunks = collect_text_chunks(text_node, &pos_list, state, tree);
+    let wrap_area = wrap::resolve_area(text_node, writing_mode, state);
+    if wrap_area.is_some() {
+        // Only the first character can have an absolute position in an auto-wrapped text.
+        for pos in pos_list.iter_mut().skip(1) {
+            pos.x = None;
+            pos.y = None;
+        }
+    }
+
+    let chunks = collect_text_chunks(text_node, &pos_list, state, tree);
+    let mut chunks = wrap::break_lines(chunks, wrap_area.as_ref(), text_node, writing_mode, state);
     let mut char_offset = 0;
     let mut last_x = 0.0;
     let mut last_y = 0.0;
@@ -122,26 +136,30 @@ fn text_to_paths(
         shaper::apply_writing_mode(writing_mode, &mut clusters);
         shaper::apply_letter_spacing(&chunk, &mut clusters);
         shaper::apply_word_spacing(&chunk, &mut clusters);
+        shaper::apply_hanging_spaces(&chunk, &mut clusters);
+        shaper::apply_length_adjust(&chunk, &mut clusters);
         let curr_pos = shaper::resolve_clusters_positions(
             chunk, char_offset, &pos_list, &rotate_list, writing_mode, &mut clusters
         );
 
+        let mut chunk_ts = tree::Transform::default();
         if writing_mode == WritingMode::TopToBottom {
             if let TextFlow::Horizontal = chunk.text_flow {
-                text_ts.rotate_at(90.0, x, y);
+                chunk_ts.rotate_at(90.0, x, y);
             }
         }
 
         for span in &mut chunk.spans {
             let decoration_spans = collect_decoration_spans(span, &clusters);
 
-            let mut span_ts = text_ts.clone();
+            let mut span_ts = chunk_ts.clone();
             span_ts.translate(x, y);
             if let TextFlow::Horizontal = chunk.text_flow {
                 // In case of a horizontal flow, shift transform and not clusters,
                 // because clusters can be rotated and an additional shift will lead
                 // to invalid results.
-                span_ts.translate(0.0, -span.baseline_shift);
+                let baseline = shaper::resolve_baseline(span, writing_mode);
+                span_ts.translate(0.0, baseline - span.baseline_shift);
             }
 
             if let Some(decoration) = span.decoration.underline.take() {
@@ -245,6 +263,8 @@ fn convert_span(
         fill,
         stroke: span.stroke.take(),
         rendering_mode: tree::ShapeRendering::default(),
+        paint_order: span.paint_order,
+        vector_effect: tree::VectorEffect::default(),
         data: Rc::new(path_data),
     };
 
@@ -355,6 +375,7 @@ fn convert_decoration(
         visibility: span.visibility,
         fill: decoration.fill.take(),
         stroke: decoration.stroke.take(),
+        paint_order: decoration.paint_order,
         data: Rc::new(path),
         .. tree::Path::default()
     }
@@ -421,11 +442,21 @@ fn paint_server_to_user_space_on_use(
                 rg.base.transform.prepend(&ts);
                 rg.base.units = tree::Units::UserSpaceOnUse;
             }
+            tree::NodeKind::MeshGradient(ref mut mg) => {
+                mg.id = new_id.clone();
+                mg.transform.prepend(&ts);
+                mg.units = tree::Units::UserSpaceOnUse;
+            }
             tree::NodeKind::Pattern(ref mut patt) => {
                 patt.id = new_id.clone();
                 patt.transform.prepend(&ts);
                 patt.units = tree::Units::UserSpaceOnUse;
             }
+            tree::NodeKind::Hatch(ref mut hatch) => {
+                hatch.id = new_id.clone();
+                hatch.transform.prepend(&ts);
+                hatch.units = tree::Units::UserSpaceOnUse;
+            }
             _ => {}
         }
 
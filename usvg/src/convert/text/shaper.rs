@@ -2,6 +2,9 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::cmp;
+use std::ops::Range;
+
 use kurbo::{ParamCurveArclen, ParamCurve, ParamCurveDeriv};
 use harfbuzz_rs as harfbuzz;
 use unicode_vo::Orientation as CharOrientation;
@@ -10,14 +13,24 @@ use ttf_parser::GlyphId;
 
 use crate::{tree, fontdb_ext, convert::prelude::*};
 use crate::tree::CubicBezExt;
-use crate::fontdb_ext::DatabaseExt;
+use crate::fontdb_ext::{DatabaseExt, FontSource};
+use super::svg_font::SvgFont;
+use super::wrap::is_hanging_char;
 use super::convert::{
     ByteIndex,
+    AlignmentBaseline,
+    BidiEmbedding,
     CharacterPosition,
+    Direction,
+    DominantBaseline,
+    FontFeature,
+    LengthAdjust,
+    SmallCaps,
     TextAnchor,
     TextChunk,
     TextFlow,
     TextPath,
+    TextSpan,
     WritingMode,
 };
 
@@ -117,6 +130,27 @@ impl OutlinedCluster {
 }
 
 
+/// A font feature applied to a text chunk range.
+#[derive(Clone, Copy)]
+struct RangedFeature {
+    feature: FontFeature,
+    start: usize,
+    end: usize,
+}
+
+
+/// A BIDI run.
+///
+/// Contains a byte range in the text chunk and the RTL flag.
+type BidiRun = (Range<usize>, bool);
+
+
+/// A synthesized small caps font size multiplier.
+///
+/// Same as in browsers.
+const SMALL_CAPS_SCALE: f64 = 0.7;
+
+
 /// An iterator over glyph clusters.
 ///
 /// Input:  0 2 2 2 3 4 4 5 5
@@ -163,9 +197,26 @@ pub fn outline_chunk(
     chunk: &TextChunk,
     state: &State,
 ) -> Vec<OutlinedCluster> {
+    // Features are collected from all spans, so the text will be shaped
+    // the same way by each span font.
+    let mut features = Vec::new();
+    for span in &chunk.spans {
+        for feature in &span.font_features {
+            features.push(RangedFeature { feature: *feature, start: span.start, end: span.end });
+        }
+    }
+
+    let small_caps = synthesize_small_caps(chunk, state);
+    let text = match small_caps {
+        Some((ref text, _)) => text.as_str(),
+        None => chunk.text.as_str(),
+    };
+
+    let bidi_runs = resolve_bidi_runs(chunk);
+
     let mut glyphs = Vec::new();
     for span in &chunk.spans {
-        let tmp_glyphs = shape_text(&chunk.text, span.font, state);
+        let tmp_glyphs = shape_text(text, span.font, &features, &bidi_runs, state);
 
         // Do nothing with the first run.
         if glyphs.is_empty() {
@@ -192,24 +243,209 @@ pub fn outline_chunk(
     let mut clusters = Vec::new();
     for (range, byte_idx) in GlyphClusters::new(&glyphs) {
         if let Some(span) = chunk.span_at(byte_idx) {
-            let db = &state.opt.fontdb;
-            clusters.push(outline_cluster(&glyphs[range], &chunk.text, span.font_size, db));
+            clusters.push(outline_cluster(&glyphs[range], &chunk.text, span.font_size, state));
+        }
+    }
+
+    if let Some((_, ref scaled)) = small_caps {
+        let ts = tree::Transform::new_scale(SMALL_CAPS_SCALE, SMALL_CAPS_SCALE);
+        for cluster in &mut clusters {
+            if scaled.binary_search(&cluster.byte_idx.value()).is_ok() {
+                cluster.path.transform(ts);
+                cluster.advance *= SMALL_CAPS_SCALE;
+            }
         }
     }
 
     clusters
 }
 
+/// Resolves BIDI runs of a text chunk in the visual order.
+fn resolve_bidi_runs(chunk: &TextChunk) -> Vec<BidiRun> {
+    use unicode_bidi::{BidiInfo, Level, ParagraphInfo};
+
+    let para_level = match chunk.direction {
+        Direction::Ltr => Level::ltr(),
+        Direction::Rtl => Level::rtl(),
+    };
+
+    if chunk.spans.iter().all(|span| span.bidi_embeddings.is_empty()) {
+        return collect_bidi_runs(&BidiInfo::new(&chunk.text, Some(para_level)));
+    }
+
+    // Text outside of spans doesn't have any embeddings,
+    // so it will be resolved using the paragraph level.
+    let mut segments: Vec<(&[BidiEmbedding], Range<usize>)> = Vec::new();
+    let mut pos = 0;
+    for span in &chunk.spans {
+        if span.start > pos {
+            segments.push((&[], pos..span.start));
+        }
+
+        segments.push((&span.bidi_embeddings, span.start..span.end));
+        pos = span.end;
+    }
+
+    if pos < chunk.text.len() {
+        segments.push((&[], pos..chunk.text.len()));
+    }
+
+    // Explicit embeddings can be set only via formatting characters,
+    // so we have to create a new text and then map the resolved levels back.
+    let mut text = String::with_capacity(chunk.text.len() + 16);
+    let mut mapping = Vec::with_capacity(chunk.text.len());
+    let mut stack: &[BidiEmbedding] = &[];
+    for (embeddings, range) in segments {
+        let common = stack.iter().zip(embeddings).take_while(|(a, b)| a == b).count();
+
+        // Close embeddings that are not used by the current span.
+        for _ in common..stack.len() {
+            text.push('\u{202C}'); // POP DIRECTIONAL FORMATTING
+        }
+
+        for embedding in &embeddings[common..] {
+            text.push(match (embedding.direction, embedding.is_override) {
+                (Direction::Ltr, false) => '\u{202A}', // LEFT-TO-RIGHT EMBEDDING
+                (Direction::Rtl, false) => '\u{202B}', // RIGHT-TO-LEFT EMBEDDING
+                (Direction::Ltr, true) => '\u{202D}', // LEFT-TO-RIGHT OVERRIDE
+                (Direction::Rtl, true) => '\u{202E}', // RIGHT-TO-LEFT OVERRIDE
+            });
+        }
+
+        stack = embeddings;
+
+        let offset = text.len();
+        mapping.extend((0..range.end - range.start).map(|i| offset + i));
+        text.push_str(&chunk.text[range]);
+    }
+
+    debug_assert_eq!(mapping.len(), chunk.text.len());
+
+    let info = BidiInfo::new(&text, Some(para_level));
+    let info = BidiInfo {
+        text: &chunk.text,
+        original_classes: mapping.iter().map(|i| info.original_classes[*i]).collect(),
+        levels: mapping.iter().map(|i| info.levels[*i]).collect(),
+        paragraphs: vec![ParagraphInfo {
+            range: 0..chunk.text.len(),
+            level: info.paragraphs[0].level,
+        }],
+    };
+
+    collect_bidi_runs(&info)
+}
+
+fn collect_bidi_runs(info: &unicode_bidi::BidiInfo) -> Vec<BidiRun> {
+    let paragraph = &info.paragraphs[0];
+    let line = paragraph.range.clone();
+
+    let (levels, runs) = info.visual_runs(paragraph, line);
+    runs.into_iter()
+        .filter(|run| !run.is_empty())
+        .map(|run| {
+            let is_rtl = levels[run.start].is_rtl();
+            (run, is_rtl)
+        })
+        .collect()
+}
+
+/// Prepares a text for small caps synthesis.
+///
+/// Small caps should be synthesized only when a font doesn't support them.
+/// In this case, lowercase letters will be replaced with uppercase ones
+/// and rendered using a smaller font size.
+///
+/// Returns a text that should be used for shaping and byte indices
+/// of characters that should be scaled down.
+fn synthesize_small_caps(
+    chunk: &TextChunk,
+    state: &State,
+) -> Option<(String, Vec<usize>)> {
+    let spans: Vec<_> = chunk.spans.iter().filter(|span| {
+        span.small_caps != SmallCaps::None &&
+        !has_small_caps(&chunk.text[span.start..span.end], span.font, state)
+    }).collect();
+
+    if spans.is_empty() {
+        return None;
+    }
+
+    let mut text = String::with_capacity(chunk.text.len());
+    let mut scaled = Vec::new();
+    for (idx, c) in chunk.text.char_indices() {
+        let mut new_c = c;
+        if let Some(span) = spans.iter().find(|span| span.contains(ByteIndex::new(idx))) {
+            if c.is_lowercase() {
+                // Text length must be preserved, so we cannot use
+                // characters with a multi-character or a longer uppercase.
+                let mut upper = c.to_uppercase();
+                if let (Some(u), None) = (upper.next(), upper.next()) {
+                    if u.len_utf8() == c.len_utf8() {
+                        new_c = u;
+                        scaled.push(idx);
+                    }
+                }
+            } else if span.small_caps == SmallCaps::All && c.is_uppercase() {
+                scaled.push(idx);
+            }
+        }
+
+        text.push(new_c);
+    }
+
+    Some((text, scaled))
+}
+
+/// Checks that a font supports small caps for the specified text.
+///
+/// Instead of parsing the `GSUB` table, we simply check that the `smcp` feature
+/// affects the shaping.
+fn has_small_caps(
+    text: &str,
+    font: fontdb_ext::Font,
+    state: &State,
+) -> bool {
+    let id = match font.source {
+        FontSource::Database(id) => id,
+        // SVG fonts do not support OpenType features.
+        FontSource::Svg(_) => return false,
+    };
+
+    let text = text.to_lowercase();
+    let res = state.opt.fontdb.with_face_data(id, |font_data, face_index| -> bool {
+        let hb_face = harfbuzz::Face::from_bytes(font_data, face_index);
+        let hb_font = harfbuzz::Font::new(hb_face);
+
+        let shape = |features: &[harfbuzz::Feature]| -> Vec<u32> {
+            let buffer = harfbuzz::UnicodeBuffer::new().add_str(&text);
+            let output = harfbuzz::shape(&hb_font, buffer, features);
+            output.get_glyph_infos().iter().map(|info| info.codepoint).collect()
+        };
+
+        let smcp = harfbuzz::Feature::new(to_harfbuzz_tag(b"smcp"), 1, ..);
+        shape(&[]) != shape(&[smcp])
+    });
+
+    res.unwrap_or(false)
+}
+
+fn to_harfbuzz_tag(tag: &[u8; 4]) -> harfbuzz::Tag {
+    harfbuzz::Tag::new(tag[0] as char, tag[1] as char, tag[2] as char, tag[3] as char)
+}
+
 /// Text shaping with font fallback.
 fn shape_text(
     text: &str,
     font: fontdb_ext::Font,
+    features: &[RangedFeature],
+    bidi_runs: &[BidiRun],
     state: &State,
 ) -> Vec<Glyph> {
-    let mut glyphs = shape_text_with_font(text, font, state).unwrap_or_default();
+    let mut glyphs = shape_text_with_font(text, font, features, bidi_runs, state)
+        .unwrap_or_default();
 
     // Remember all fonts used for shaping.
-    let mut used_fonts = vec![font.id];
+    let mut used_fonts = vec![font.source];
 
     // Loop until all glyphs become resolved or until no more fonts are left.
     'outer: loop {
@@ -228,8 +464,9 @@ fn shape_text(
             };
 
             // Shape again, using a new font.
-            let fallback_glyphs = shape_text_with_font(text, fallback_font, state)
-                .unwrap_or_default();
+            let fallback_glyphs = shape_text_with_font(
+                text, fallback_font, features, bidi_runs, state,
+            ).unwrap_or_default();
 
             let all_matched = fallback_glyphs.iter().all(|g| !g.is_missing());
             if all_matched {
@@ -254,7 +491,7 @@ fn shape_text(
             }
 
             // Remember this font.
-            used_fonts.push(fallback_font.id);
+            used_fonts.push(fallback_font.source);
         } else {
             break 'outer;
         }
@@ -278,57 +515,150 @@ fn shape_text(
 fn shape_text_with_font(
     text: &str,
     font: fontdb_ext::Font,
+    features: &[RangedFeature],
+    bidi_runs: &[BidiRun],
     state: &State,
 ) -> Option<Vec<Glyph>> {
-    state.opt.fontdb.with_face_data(font.id, |font_data, face_index| -> Option<Vec<Glyph>> {
-        let hb_face = harfbuzz::Face::from_bytes(font_data, face_index);
-        let hb_font = harfbuzz::Font::new(hb_face);
-
-        let bidi_info = unicode_bidi::BidiInfo::new(text, Some(unicode_bidi::Level::ltr()));
-        let paragraph = &bidi_info.paragraphs[0];
-        let line = paragraph.range.clone();
+    match font.source {
+        FontSource::Database(id) => {
+            state.opt.fontdb.with_face_data(id, |font_data, face_index| -> Vec<Glyph> {
+                let hb_face = harfbuzz::Face::from_bytes(font_data, face_index);
+                let hb_font = harfbuzz::Font::new(hb_face);
+
+                shape_bidi_runs(text, bidi_runs, |run, is_rtl| {
+                    let hb_direction = if is_rtl {
+                        harfbuzz::Direction::Rtl
+                    } else {
+                        harfbuzz::Direction::Ltr
+                    };
+
+                    let buffer = harfbuzz::UnicodeBuffer::new()
+                        .add_str(&text[run.clone()])
+                        .set_direction(hb_direction);
+
+                    // Features ranges are relative to the run.
+                    let hb_features: Vec<_> = features.iter().filter_map(|f| {
+                        let start = cmp::max(f.start, run.start);
+                        let end = cmp::min(f.end, run.end);
+                        if start < end {
+                            let tag = to_harfbuzz_tag(&f.feature.tag);
+                            let range = (start - run.start)..(end - run.start);
+                            Some(harfbuzz::Feature::new(tag, f.feature.value, range))
+                        } else {
+                            None
+                        }
+                    }).collect();
+
+                    let output = harfbuzz::shape(&hb_font, buffer, &hb_features);
+
+                    let positions = output.get_glyph_positions();
+                    let infos = output.get_glyph_infos();
+
+                    positions.iter().zip(infos).map(|(pos, info)| {
+                        Glyph {
+                            byte_idx: ByteIndex::new(info.cluster as usize),
+                            id: GlyphId(info.codepoint as u16),
+                            dx: pos.x_offset,
+                            dy: pos.y_offset,
+                            width: pos.x_advance,
+                            font,
+                        }
+                    }).collect()
+                })
+            })
+        }
+        FontSource::Svg(idx) => {
+            let svg_font = state.svg_fonts.get(idx)?;
+            Some(shape_bidi_runs(text, bidi_runs, |run, is_rtl| {
+                let is_kerning = |idx| is_kerning_enabled(features, run.start + idx);
+                shape_with_svg_font(&text[run.clone()], is_rtl, is_kerning, font, svg_font)
+            }))
+        }
+    }
+}
 
-        let mut glyphs = Vec::new();
+/// Checks that the `kern` feature is not disabled at a byte index.
+///
+/// The last matching feature wins, just like in `harfbuzz`.
+fn is_kerning_enabled(features: &[RangedFeature], idx: usize) -> bool {
+    let feature = features.iter().rev()
+        .find(|f| &f.feature.tag == b"kern" && f.start <= idx && idx < f.end);
+
+    match feature {
+        Some(f) => f.feature.value != 0,
+        None => true,
+    }
+}
 
-        let (levels, runs) = bidi_info.visual_runs(&paragraph, line);
-        for run in runs.iter() {
-            let sub_text = &text[run.clone()];
-            if sub_text.is_empty() {
-                continue;
-            }
+/// Shapes BIDI runs in the visual order.
+///
+/// `shape_run` accepts a run range and a direction flag and must return glyphs
+/// in the visual order with byte indices relative to the run start.
+fn shape_bidi_runs<F>(text: &str, bidi_runs: &[BidiRun], mut shape_run: F) -> Vec<Glyph>
+    where F: FnMut(Range<usize>, bool) -> Vec<Glyph>
+{
+    let mut glyphs = Vec::new();
+    for (run, is_rtl) in bidi_runs {
+        for mut glyph in shape_run(run.clone(), *is_rtl) {
+            let idx = run.start + glyph.byte_idx.value();
+            debug_assert!(text.get(idx..).is_some());
 
-            let hb_direction = if levels[run.start].is_rtl() {
-                harfbuzz::Direction::Rtl
-            } else {
-                harfbuzz::Direction::Ltr
-            };
+            glyph.byte_idx = ByteIndex::new(idx);
+            glyphs.push(glyph);
+        }
+    }
 
-            let buffer = harfbuzz::UnicodeBuffer::new()
-                .add_str(sub_text)
-                .set_direction(hb_direction);
+    glyphs
+}
 
-            let output = harfbuzz::shape(&hb_font, buffer, &[]);
+/// Shapes a single-direction text run using an SVG font.
+///
+/// SVG fonts do not have any shaping rules, so we are simply mapping
+/// the text to glyphs and applying the kerning.
+///
+/// `is_kerning` checks that the kerning is enabled at a byte index.
+fn shape_with_svg_font<F>(
+    text: &str,
+    is_rtl: bool,
+    is_kerning: F,
+    font: fontdb_ext::Font,
+    svg_font: &SvgFont,
+) -> Vec<Glyph>
+    where F: Fn(usize) -> bool
+{
+    let mut glyphs: Vec<Glyph> = Vec::new();
+    let mut idx = 0;
+    while idx < text.len() {
+        let (id, len) = svg_font.glyph_at(&text[idx..]);
+        let mut width = svg_font.advance(id).round() as i32;
+
+        if let (Some(prev), true) = (glyphs.last_mut(), is_kerning(idx)) {
+            let k = svg_font.kerning(prev.id.0, id).round() as i32;
+            // In the RTL text, the current glyph will be placed before the previous one.
+            if is_rtl {
+                width -= k;
+            } else {
+                prev.width -= k;
+            }
+        }
 
-            let positions = output.get_glyph_positions();
-            let infos = output.get_glyph_infos();
+        glyphs.push(Glyph {
+            byte_idx: ByteIndex::new(idx),
+            id: GlyphId(id),
+            dx: 0,
+            dy: 0,
+            width,
+            font,
+        });
 
-            for (pos, info) in positions.iter().zip(infos) {
-                let idx = run.start + info.cluster as usize;
-                debug_assert!(text.get(idx..).is_some());
+        idx += len;
+    }
 
-                glyphs.push(Glyph {
-                    byte_idx: ByteIndex::new(idx),
-                    id: GlyphId(info.codepoint as u16),
-                    dx: pos.x_offset,
-                    dy: pos.y_offset,
-                    width: pos.x_advance,
-                    font,
-                });
-            }
-        }
+    if is_rtl {
+        glyphs.reverse();
+    }
 
-        Some(glyphs)
-    })?
+    glyphs
 }
 
 /// Outlines a glyph cluster.
@@ -338,7 +668,7 @@ fn outline_cluster(
     glyphs: &[Glyph],
     text: &str,
     font_size: f64,
-    db: &fontdb::Database,
+    state: &State,
 ) -> OutlinedCluster {
     debug_assert!(!glyphs.is_empty());
 
@@ -347,7 +677,11 @@ fn outline_cluster(
     let mut x = 0.0;
 
     for glyph in glyphs {
-        let mut outline = db.outline(glyph.font.id, glyph.id).unwrap_or_default();
+        let outline = match glyph.font.source {
+            FontSource::Database(id) => state.opt.fontdb.outline(id, glyph.id),
+            FontSource::Svg(idx) => state.svg_fonts.get(idx).and_then(|f| f.outline(glyph.id.0)),
+        };
+        let mut outline = outline.unwrap_or_default();
 
         let sx = glyph.font.scale(font_size);
 
@@ -400,32 +734,40 @@ fn outline_cluster(
 /// This is a rudimentary font fallback algorithm.
 fn find_font_for_char(
     c: char,
-    exclude_fonts: &[fontdb::ID],
+    exclude_fonts: &[FontSource],
     state: &State,
 ) -> Option<fontdb_ext::Font> {
-    let base_font_id = exclude_fonts[0];
+    // SVG fonts do not have a style, so we can use any face as a fallback.
+    let (base_face, base_family) = match exclude_fonts[0] {
+        FontSource::Database(id) => {
+            let face = state.opt.fontdb.face(id)?;
+            (Some(face), face.family.as_str())
+        }
+        FontSource::Svg(idx) => (None, state.svg_fonts.get(idx)?.family.as_str()),
+    };
 
     // Iterate over fonts and check if any of them support the specified char.
     for face in state.opt.fontdb.faces() {
         // Ignore fonts, that were used for shaping already.
-        if exclude_fonts.contains(&face.id) {
+        if exclude_fonts.contains(&FontSource::Database(face.id)) {
             continue;
         }
 
         // Check that the new face has the same style.
-        let base_face = state.opt.fontdb.face(base_font_id)?;
-        if  base_face.style != face.style &&
-            base_face.weight != face.weight &&
-            base_face.stretch != face.stretch
-        {
-            continue;
+        if let Some(base_face) = base_face {
+            if  base_face.style != face.style &&
+                base_face.weight != face.weight &&
+                base_face.stretch != face.stretch
+            {
+                continue;
+            }
         }
 
         if !state.opt.fontdb.has_char(face.id, c) {
             continue;
         }
 
-        warn!("Fallback from {} to {}.", base_face.family, face.family);
+        warn!("Fallback from {} to {}.", base_family, face.family);
         return state.opt.fontdb.load_font(face.id);
     }
 
@@ -545,7 +887,7 @@ fn resolve_clusters_positions_path(
         }
 
         let baseline_shift = chunk.span_at(cluster.byte_idx)
-            .map(|span| span.baseline_shift)
+            .map(|span| span.baseline_shift - resolve_baseline(span, writing_mode))
             .unwrap_or(0.0);
 
         // Shift only by `dy` since we already applied `dx`
@@ -568,6 +910,58 @@ fn resolve_clusters_positions_path(
     (last_x, last_y)
 }
 
+/// Resolves a span's baseline offset produced by
+/// `dominant-baseline` and `alignment-baseline`.
+///
+/// Positive values move the text down.
+///
+/// Only the horizontal text is affected, since the vertical one is always
+/// aligned by the central baseline.
+pub fn resolve_baseline(span: &TextSpan, writing_mode: WritingMode) -> f64 {
+    if writing_mode != WritingMode::LeftToRight {
+        return 0.0;
+    }
+
+    let baseline = match span.alignment_baseline {
+        AlignmentBaseline::Auto => {
+            match span.dominant_baseline {
+                  DominantBaseline::Auto
+                | DominantBaseline::UseScript
+                | DominantBaseline::NoChange
+                | DominantBaseline::ResetSize
+                | DominantBaseline::Alphabetic => AlignmentBaseline::Alphabetic,
+                DominantBaseline::Ideographic => AlignmentBaseline::Ideographic,
+                DominantBaseline::Hanging => AlignmentBaseline::Hanging,
+                DominantBaseline::Mathematical => AlignmentBaseline::Mathematical,
+                DominantBaseline::Central => AlignmentBaseline::Central,
+                DominantBaseline::Middle => AlignmentBaseline::Middle,
+                DominantBaseline::TextAfterEdge => AlignmentBaseline::TextAfterEdge,
+                DominantBaseline::TextBeforeEdge => AlignmentBaseline::TextBeforeEdge,
+            }
+        }
+        v => v,
+    };
+
+    let font = &span.font;
+    let font_size = span.font_size;
+    match baseline {
+          AlignmentBaseline::Auto
+        | AlignmentBaseline::Baseline
+        | AlignmentBaseline::Alphabetic => 0.0,
+          AlignmentBaseline::BeforeEdge
+        | AlignmentBaseline::TextBeforeEdge => font.ascent(font_size),
+          AlignmentBaseline::AfterEdge
+        | AlignmentBaseline::TextAfterEdge => font.descent(font_size),
+        AlignmentBaseline::Middle => font.x_height(font_size) / 2.0,
+        AlignmentBaseline::Central => font.ascent(font_size) - font.height(font_size) / 2.0,
+        // Fonts rarely provide the `BASE` table, so we are using
+        // the same approximations as browsers do.
+        AlignmentBaseline::Ideographic => font.descent(font_size),
+        AlignmentBaseline::Hanging => font.ascent(font_size) * 0.8,
+        AlignmentBaseline::Mathematical => font.ascent(font_size) * 0.5,
+    }
+}
+
 fn clusters_length(clusters: &[OutlinedCluster]) -> f64 {
     clusters.iter().fold(0.0, |w, cluster| w + cluster.advance)
 }
@@ -791,6 +1185,81 @@ pub fn apply_word_spacing(
     }
 }
 
+/// Hides the trailing white space of a line.
+///
+/// [In the CSS spec](https://www.w3.org/TR/css-text-3/#white-space-phase-2).
+pub fn apply_hanging_spaces(
+    chunk: &TextChunk,
+    clusters: &mut [OutlinedCluster],
+) {
+    if !chunk.is_line {
+        return;
+    }
+
+    let hang_start = chunk.text.trim_end_matches(is_hanging_char).len();
+    for cluster in clusters {
+        if cluster.byte_idx.value() >= hang_start {
+            cluster.advance = 0.0;
+            cluster.visible = false;
+        }
+    }
+}
+
+/// Applies `textLength` and `lengthAdjust` to clusters.
+///
+/// Must be called after the letter and word spacing resolving.
+pub fn apply_length_adjust(
+    chunk: &TextChunk,
+    clusters: &mut [OutlinedCluster],
+) {
+    for text_length in &chunk.text_lengths {
+        let mut count = 0;
+        let mut length = 0.0;
+        let mut last_idx = None;
+        for (i, cluster) in clusters.iter().enumerate() {
+            if text_length.contains(cluster.byte_idx) {
+                count += 1;
+                length += cluster.advance;
+                last_idx = Some(i);
+            }
+        }
+
+        if count == 0 {
+            continue;
+        }
+
+        match text_length.adjust {
+            LengthAdjust::Spacing => {
+                // The extra space should be placed only between clusters.
+                if count < 2 {
+                    continue;
+                }
+
+                let spacing = (text_length.length - length) / (count - 1) as f64;
+                for (i, cluster) in clusters.iter_mut().enumerate() {
+                    if text_length.contains(cluster.byte_idx) && Some(i) != last_idx {
+                        cluster.advance += spacing;
+                    }
+                }
+            }
+            LengthAdjust::SpacingAndGlyphs => {
+                if !length.is_valid_length() {
+                    continue;
+                }
+
+                let k = text_length.length / length;
+                let ts = tree::Transform::new_scale(k, 1.0);
+                for cluster in clusters.iter_mut() {
+                    if text_length.contains(cluster.byte_idx) {
+                        cluster.advance *= k;
+                        cluster.path.transform(ts);
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// Checks that the selected character is a word separator.
 ///
 /// According to: https://www.w3.org/TR/css-text-3/#word-separator
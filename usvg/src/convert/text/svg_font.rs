@@ -0,0 +1,314 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::{fontdb_ext, svgtree, tree};
+use crate::fontdb_ext::{FontMetrics, FontSource};
+use crate::convert::prelude::*;
+
+
+/// An SVG font.
+///
+/// Built from the `font` element and its `font-face`, `glyph`,
+/// `missing-glyph` and `hkern` children.
+pub struct SvgFont {
+    /// A font family name from the `font-face` element.
+    pub family: String,
+
+    /// Font metrics.
+    pub font: fontdb_ext::Font,
+
+    /// Glyphs in the document order.
+    ///
+    /// Glyph ID is an index in this list + 1, since 0 is reserved for the missing glyph.
+    glyphs: Vec<SvgGlyph>,
+
+    missing_glyph: SvgGlyph,
+
+    kerning: Vec<KerningPair>,
+}
+
+struct SvgGlyph {
+    unicode: String,
+    name: String,
+    /// An advance in font units.
+    advance: f64,
+    /// An outline in font units.
+    path: Option<tree::SharedPathData>,
+}
+
+struct KerningPair {
+    u1: Vec<UnicodeRange>,
+    g1: Vec<String>,
+    u2: Vec<UnicodeRange>,
+    g2: Vec<String>,
+    k: f64,
+}
+
+enum UnicodeRange {
+    /// A literal string.
+    String(String),
+    /// An inclusive code points range.
+    Range(u32, u32),
+}
+
+impl SvgFont {
+    /// Returns a glyph ID and a matched text length in bytes
+    /// for a glyph at the start of the `text`.
+    ///
+    /// As required by the SVG spec, the first glyph in the document order
+    /// which `unicode` matches the text start will be selected.
+    /// So ligatures should be defined before single characters.
+    ///
+    /// When no glyphs were matched, returns 0 and the length of the first character.
+    pub fn glyph_at(&self, text: &str) -> (u16, usize) {
+        for (i, glyph) in self.glyphs.iter().enumerate() {
+            if !glyph.unicode.is_empty() && text.starts_with(&glyph.unicode) {
+                return (i as u16 + 1, glyph.unicode.len());
+            }
+        }
+
+        let len = text.chars().next().map(char::len_utf8).unwrap_or(0);
+        (0, len)
+    }
+
+    /// Returns a glyph advance in font units.
+    pub fn advance(&self, id: u16) -> f64 {
+        self.glyph(id).advance
+    }
+
+    /// Returns a glyph outline in font units.
+    pub fn outline(&self, id: u16) -> Option<tree::PathData> {
+        self.glyph(id).path.as_ref().map(|path| (**path).clone())
+    }
+
+    /// Returns a kerning value between two glyphs in font units.
+    ///
+    /// A positive value moves glyphs closer to each other.
+    pub fn kerning(&self, id1: u16, id2: u16) -> f64 {
+        if id1 == 0 || id2 == 0 {
+            return 0.0;
+        }
+
+        let glyph1 = self.glyph(id1);
+        let glyph2 = self.glyph(id2);
+        for pair in &self.kerning {
+            if  is_kerning_match(glyph1, &pair.u1, &pair.g1) &&
+                is_kerning_match(glyph2, &pair.u2, &pair.g2)
+            {
+                return pair.k;
+            }
+        }
+
+        0.0
+    }
+
+    fn glyph(&self, id: u16) -> &SvgGlyph {
+        match id {
+            0 => &self.missing_glyph,
+            _ => self.glyphs.get(id as usize - 1).unwrap_or(&self.missing_glyph),
+        }
+    }
+}
+
+fn is_kerning_match(glyph: &SvgGlyph, unicode: &[UnicodeRange], names: &[String]) -> bool {
+    if !glyph.name.is_empty() && names.iter().any(|name| *name == glyph.name) {
+        return true;
+    }
+
+    if glyph.unicode.is_empty() {
+        return false;
+    }
+
+    let mut chars = glyph.unicode.chars();
+    let single_char = match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c as u32),
+        _ => None,
+    };
+
+    unicode.iter().any(|range| {
+        match *range {
+            UnicodeRange::String(ref s) => *s == glyph.unicode,
+            UnicodeRange::Range(start, end) => {
+                single_char.map(|c| c >= start && c <= end).unwrap_or(false)
+            }
+        }
+    })
+}
+
+
+/// Collects all valid SVG fonts in the document.
+///
+/// Fonts are returned in the document order, so an index in the returned list
+/// can be used as `FontSource::Svg`.
+pub fn collect(doc: &svgtree::Document) -> Vec<SvgFont> {
+    let mut fonts = Vec::new();
+    for node in doc.descendants() {
+        if node.has_tag_name(EId::Font) {
+            if let Some(font) = convert_font(node, fonts.len()) {
+                fonts.push(font);
+            }
+        }
+    }
+
+    fonts
+}
+
+fn convert_font(node: svgtree::Node, idx: usize) -> Option<SvgFont> {
+    let face = try_opt_warn_or!(
+        node.children().find(|n| n.has_tag_name(EId::FontFace)), None,
+        "Font '{}' has no 'font-face' element. Skipped.", node.element_id()
+    );
+
+    let family = face.attribute::<&str>(AId::FontFamily).map(parse_family_name).unwrap_or_default();
+    if family.is_empty() {
+        warn!("Font '{}' has no 'font-family'. Skipped.", node.element_id());
+        return None;
+    }
+
+    let units_per_em = face.attribute(AId::UnitsPerEm).unwrap_or(1000.0).round();
+    if !(units_per_em >= 1.0) {
+        warn!("Font '{}' has an invalid 'units-per-em'. Skipped.", node.element_id());
+        return None;
+    }
+
+    // Font metrics are stored as `i16`, so larger values are clamped.
+    // The lower bound is symmetric, so the descent can be negated.
+    let max = std::i16::MAX as f64;
+    let units_per_em = units_per_em.min(max);
+    let metric = |aid: AId| -> Option<i16> {
+        face.attribute::<f64>(aid).map(|n| n.round().max(-max).min(max) as i16)
+    };
+
+    // Unlike TrueType, SVG fonts have a positive descent.
+    let ascent = metric(AId::Ascent).unwrap_or(units_per_em as i16);
+    let descent = -metric(AId::Descent).unwrap_or(0);
+
+    let underline = match (metric(AId::UnderlinePosition), metric(AId::UnderlineThickness)) {
+        (Some(position), Some(thickness)) => Some(ttf_parser::LineMetrics { position, thickness }),
+        _ => None,
+    };
+
+    let font = fontdb_ext::Font::from_metrics(FontSource::Svg(idx), FontMetrics {
+        units_per_em: units_per_em as u16,
+        ascent,
+        descent,
+        x_height: metric(AId::XHeight),
+        underline,
+        line_through_position: metric(AId::StrikethroughPosition),
+        subscript_offset: None,
+        superscript_offset: None,
+    });
+
+    let default_advance = node.attribute(AId::HorizAdvX).unwrap_or(0.0);
+
+    let mut glyphs = Vec::new();
+    let mut missing_glyph = None;
+    let mut kerning = Vec::new();
+    for child in node.children() {
+        match child.tag_name() {
+            Some(EId::Glyph) => {
+                // Glyph ID is an `u16` and 0 is reserved.
+                if glyphs.len() < std::u16::MAX as usize - 1 {
+                    glyphs.push(convert_glyph(child, default_advance));
+                }
+            }
+            Some(EId::MissingGlyph) => {
+                if missing_glyph.is_none() {
+                    missing_glyph = Some(convert_glyph(child, default_advance));
+                }
+            }
+            Some(EId::Hkern) => {
+                if let Some(pair) = convert_kerning_pair(child) {
+                    kerning.push(pair);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let missing_glyph = missing_glyph.unwrap_or_else(|| SvgGlyph {
+        unicode: String::new(),
+        name: String::new(),
+        advance: default_advance,
+        path: None,
+    });
+
+    Some(SvgFont {
+        family,
+        font,
+        glyphs,
+        missing_glyph,
+        kerning,
+    })
+}
+
+fn convert_glyph(node: svgtree::Node, default_advance: f64) -> SvgGlyph {
+    SvgGlyph {
+        unicode: node.attribute::<&str>(AId::Unicode).unwrap_or("").to_string(),
+        name: node.attribute::<&str>(AId::GlyphName).unwrap_or("").trim().to_string(),
+        advance: node.attribute(AId::HorizAdvX).unwrap_or(default_advance),
+        path: node.attribute(AId::D),
+    }
+}
+
+fn convert_kerning_pair(node: svgtree::Node) -> Option<KerningPair> {
+    let k = node.attribute(AId::K)?;
+    let u1 = parse_unicode_ranges(node.attribute(AId::U1).unwrap_or(""));
+    let g1 = parse_names(node.attribute(AId::G1).unwrap_or(""));
+    let u2 = parse_unicode_ranges(node.attribute(AId::U2).unwrap_or(""));
+    let g2 = parse_names(node.attribute(AId::G2).unwrap_or(""));
+
+    if (u1.is_empty() && g1.is_empty()) || (u2.is_empty() && g2.is_empty()) {
+        return None;
+    }
+
+    Some(KerningPair { u1, g1, u2, g2, k })
+}
+
+fn parse_family_name(text: &str) -> String {
+    text.trim().trim_matches(|c| c == '\'' || c == '"').trim().to_string()
+}
+
+fn parse_names(text: &str) -> Vec<String> {
+    text.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect()
+}
+
+/// Parses a list of characters and unicode ranges.
+///
+/// Like `A,U+0410-042F,U+04??`.
+fn parse_unicode_ranges(text: &str) -> Vec<UnicodeRange> {
+    let mut list = Vec::new();
+    for item in text.split(',') {
+        let item = item.trim();
+        if item.is_empty() {
+            continue;
+        }
+
+        if item.starts_with("U+") && item.len() > 2 {
+            if let Some(range) = parse_unicode_range(&item[2..]) {
+                list.push(range);
+                continue;
+            }
+        }
+
+        list.push(UnicodeRange::String(item.to_string()));
+    }
+
+    list
+}
+
+fn parse_unicode_range(text: &str) -> Option<UnicodeRange> {
+    let mut parts = text.splitn(2, '-');
+    let start = parts.next()?;
+    if let Some(end) = parts.next() {
+        let start = u32::from_str_radix(start, 16).ok()?;
+        let end = u32::from_str_radix(end, 16).ok()?;
+        return Some(UnicodeRange::Range(start, end));
+    }
+
+    // Wildcards, like `U+04??`.
+    let start = u32::from_str_radix(&start.replace('?', "0"), 16).ok()?;
+    let end = u32::from_str_radix(&text.replace('?', "F"), 16).ok()?;
+    Some(UnicodeRange::Range(start, end))
+}
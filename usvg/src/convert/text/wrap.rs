@@ -0,0 +1,418 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::ops::Range;
+
+use unicode_linebreak::BreakOpportunity;
+
+use crate::{svgtree, tree, Transform};
+use crate::convert::{prelude::*, shapes};
+use super::TextNode;
+use super::shaper;
+use super::convert::{
+    TextAnchor,
+    TextChunk,
+    TextFlow,
+    WritingMode,
+};
+
+/// An amount of lines a curve is approximated with.
+const CURVE_SEGMENTS: usize = 16;
+
+
+/// An area the text should be wrapped into.
+pub enum WrapArea {
+    /// A maximum line length set via `inline-size`.
+    InlineSize(f64),
+    /// A shape referenced via `shape-inside`.
+    Shape {
+        /// The shape outline approximated with lines.
+        edges: Vec<Edge>,
+        bbox: Rect,
+        /// The `shape-padding` value.
+        padding: f64,
+    },
+}
+
+
+#[derive(Clone, Copy)]
+pub struct Edge {
+    x1: f64,
+    y1: f64,
+    x2: f64,
+    y2: f64,
+}
+
+
+/// A line position and its maximum length.
+struct LineBox {
+    x: f64,
+    y: f64,
+    width: Option<f64>,
+}
+
+
+/// Resolves the auto-wrapped text area.
+///
+/// `shape-inside` has a higher priority than `inline-size`, but only when it's valid.
+pub fn resolve_area(
+    text_node: TextNode,
+    writing_mode: WritingMode,
+    state: &State,
+) -> Option<WrapArea> {
+    if let Some(node) = text_node.attribute::<svgtree::Node>(AId::ShapeInside) {
+        if writing_mode == WritingMode::TopToBottom {
+            warn!("'shape-inside' with a vertical writing mode is not supported.");
+        } else if let Some(area) = resolve_shape(node, text_node, state) {
+            return Some(area);
+        }
+    }
+
+    let size = text_node.try_convert_user_length(AId::InlineSize, state)?;
+    if size.is_valid_length() {
+        Some(WrapArea::InlineSize(size))
+    } else {
+        None
+    }
+}
+
+fn resolve_shape(
+    node: svgtree::Node,
+    text_node: TextNode,
+    state: &State,
+) -> Option<WrapArea> {
+    let path = shapes::convert(node, state)?;
+
+    // The shape's transform needs to be applied.
+    let mut path = path.as_ref().clone();
+    if let Some(ts) = node.attribute::<Transform>(AId::Transform) {
+        path.transform(ts);
+    }
+
+    let bbox = path.bbox()?;
+    let padding = text_node.convert_user_length(AId::ShapePadding, state, Length::zero());
+
+    Some(WrapArea::Shape {
+        edges: collect_edges(&path),
+        bbox,
+        padding: padding.max(0.0),
+    })
+}
+
+/// Splits text chunks into lines.
+///
+/// Lines are separated by preserved line feeds and, inside an auto-wrapped text,
+/// by [UAX #14](https://www.unicode.org/reports/tr14/) break opportunities.
+/// Each line is a separate text chunk with an absolute position.
+pub fn break_lines(
+    chunks: Vec<TextChunk>,
+    area: Option<&WrapArea>,
+    text_node: TextNode,
+    writing_mode: WritingMode,
+    state: &State,
+) -> Vec<TextChunk> {
+    // `nowrap` and `pre` allow only forced line breaks.
+    let allow_wrap = !matches!(text_node.find_attribute(AId::WhiteSpace),
+                               Some("nowrap") | Some("pre"));
+
+    let mut lines = Vec::new();
+    for mut chunk in chunks {
+        if let TextFlow::Path(_) = chunk.text_flow {
+            // A text on path is always a single line.
+            chunk.text = chunk.text.replace('\n', " ");
+            lines.push(chunk);
+            continue;
+        }
+
+        if area.is_none() && !chunk.text.contains('\n') {
+            lines.push(chunk);
+            continue;
+        }
+
+        break_chunk(&chunk, area, allow_wrap, writing_mode, state, &mut lines);
+    }
+
+    lines
+}
+
+fn break_chunk(
+    chunk: &TextChunk,
+    area: Option<&WrapArea>,
+    allow_wrap: bool,
+    writing_mode: WritingMode,
+    state: &State,
+    lines: &mut Vec<TextChunk>,
+) {
+    if !chunk.text_lengths.is_empty() {
+        warn!("'textLength' on a multi-line text is not supported.");
+    }
+
+    // The same metrics are used for all lines, so lines are evenly spaced.
+    let mut line_height = 0.0f64;
+    let mut ascent = 0.0f64;
+    for span in &chunk.spans {
+        line_height = line_height.max(span.font.height(span.font_size));
+        ascent = ascent.max(span.font.ascent(span.font_size));
+    }
+
+    if !line_height.is_valid_length() {
+        return;
+    }
+
+    let mut clusters = shaper::outline_chunk(chunk, state);
+    shaper::apply_letter_spacing(chunk, &mut clusters);
+    shaper::apply_word_spacing(chunk, &mut clusters);
+
+    // Clusters advances by a byte index, so we can measure any text range.
+    let mut advances = vec![0.0; chunk.text.len()];
+    for cluster in &clusters {
+        advances[cluster.byte_idx.value()] += cluster.advance;
+    }
+
+    let line_length = |range: Range<usize>| -> f64 {
+        // Trailing white space hangs and doesn't affect the line length.
+        let end = range.start + chunk.text[range.clone()].trim_end_matches(is_hanging_char).len();
+        advances[range.start..end].iter().sum()
+    };
+
+    let mut line_boxes = LineBoxes {
+        area,
+        anchor: chunk.anchor,
+        writing_mode,
+        x: chunk.x.unwrap_or(0.0),
+        y: chunk.y.unwrap_or(0.0),
+        line_height,
+        ascent,
+        idx: 0,
+    };
+
+    let mut line_box = match line_boxes.next() {
+        Some(v) => v,
+        None => return,
+    };
+
+    let mut start = 0;
+    let mut last_break = None;
+    for (idx, opportunity) in unicode_linebreak::linebreaks(&chunk.text) {
+        if let (Some(width), Some(prev_idx)) = (line_box.width, last_break) {
+            if line_length(start..idx) > width {
+                // Break the line at the previous opportunity.
+                lines.push(new_line(chunk, start..prev_idx, &line_box));
+                line_box = match line_boxes.next() {
+                    Some(v) => v,
+                    None => return,
+                };
+
+                start = prev_idx;
+                last_break = None;
+            }
+        }
+
+        match opportunity {
+            BreakOpportunity::Mandatory => {
+                lines.push(new_line(chunk, start..idx, &line_box));
+                if idx == chunk.text.len() {
+                    break;
+                }
+
+                line_box = match line_boxes.next() {
+                    Some(v) => v,
+                    None => return,
+                };
+
+                start = idx;
+                last_break = None;
+            }
+            BreakOpportunity::Allowed => {
+                if allow_wrap {
+                    last_break = Some(idx);
+                }
+            }
+        }
+    }
+}
+
+fn new_line(chunk: &TextChunk, range: Range<usize>, line_box: &LineBox) -> TextChunk {
+    let mut spans = Vec::new();
+    for span in &chunk.spans {
+        if span.start < range.end && span.end > range.start {
+            let mut span = span.clone();
+            span.start = span.start.max(range.start) - range.start;
+            span.end = span.end.min(range.end) - range.start;
+            spans.push(span);
+        }
+    }
+
+    TextChunk {
+        x: Some(line_box.x),
+        y: Some(line_box.y),
+        anchor: chunk.anchor,
+        direction: chunk.direction,
+        spans,
+        text_flow: chunk.text_flow.clone(),
+        text: chunk.text[range].to_string(),
+        text_lengths: Vec::new(),
+        is_line: true,
+    }
+}
+
+/// Checks that a character hangs at the end of a line.
+pub fn is_hanging_char(c: char) -> bool {
+    matches!(c, ' ' | '\n' | '\u{2028}' | '\u{2029}')
+}
+
+
+/// An iterator over line positions.
+struct LineBoxes<'a> {
+    area: Option<&'a WrapArea>,
+    anchor: TextAnchor,
+    writing_mode: WritingMode,
+    /// The first line position.
+    x: f64,
+    y: f64,
+    line_height: f64,
+    ascent: f64,
+    idx: usize,
+}
+
+impl Iterator for LineBoxes<'_> {
+    type Item = LineBox;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(&WrapArea::Shape { ref edges, bbox, padding }) = self.area {
+            // Lines are placed from the top of the shape and only where it's wide enough.
+            // The remaining text is not rendered.
+            loop {
+                let top = bbox.y() + padding + self.idx as f64 * self.line_height;
+                let bottom = top + self.line_height;
+                self.idx += 1;
+
+                if bottom > bbox.bottom() - padding {
+                    return None;
+                }
+
+                if let Some((left, right)) = band_extents(edges, top, bottom) {
+                    let left = left + padding;
+                    let right = right - padding;
+                    if right > left {
+                        let x = match self.anchor {
+                            TextAnchor::Start => left,
+                            TextAnchor::Middle => (left + right) / 2.0,
+                            TextAnchor::End => right,
+                        };
+
+                        return Some(LineBox { x, y: top + self.ascent, width: Some(right - left) });
+                    }
+                }
+            }
+        }
+
+        let offset = self.idx as f64 * self.line_height;
+        self.idx += 1;
+
+        // Lines in the top-to-bottom mode are going from right to left.
+        let (x, y) = match self.writing_mode {
+            WritingMode::LeftToRight => (self.x, self.y + offset),
+            WritingMode::TopToBottom => (self.x - offset, self.y),
+        };
+
+        let width = match self.area {
+            Some(WrapArea::InlineSize(size)) => Some(*size),
+            _ => None,
+        };
+
+        Some(LineBox { x, y, width })
+    }
+}
+
+
+/// Approximates a path with lines.
+///
+/// All subpaths are implicitly closed, since only the fill area is used.
+fn collect_edges(path: &tree::PathData) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    let mut push = |x1, y1, x2, y2| edges.push(Edge { x1, y1, x2, y2 });
+
+    let mut start = (0.0, 0.0);
+    let mut prev = (0.0, 0.0);
+    for seg in path.iter() {
+        match *seg {
+            tree::PathSegment::MoveTo { x, y } => {
+                push(prev.0, prev.1, start.0, start.1);
+                start = (x, y);
+                prev = start;
+            }
+            tree::PathSegment::LineTo { x, y } => {
+                push(prev.0, prev.1, x, y);
+                prev = (x, y);
+            }
+            tree::PathSegment::CurveTo { x1, y1, x2, y2, x, y } => {
+                let mut p = prev;
+                for i in 1..=CURVE_SEGMENTS {
+                    let t = i as f64 / CURVE_SEGMENTS as f64;
+                    let mt = 1.0 - t;
+                    let a = mt * mt * mt;
+                    let b = 3.0 * mt * mt * t;
+                    let c = 3.0 * mt * t * t;
+                    let d = t * t * t;
+                    let next = (
+                        a * prev.0 + b * x1 + c * x2 + d * x,
+                        a * prev.1 + b * y1 + c * y2 + d * y,
+                    );
+                    push(p.0, p.1, next.0, next.1);
+                    p = next;
+                }
+
+                prev = (x, y);
+            }
+            tree::PathSegment::ClosePath => {
+                push(prev.0, prev.1, start.0, start.1);
+                prev = start;
+            }
+        }
+    }
+
+    push(prev.0, prev.1, start.0, start.1);
+
+    edges
+}
+
+/// Returns horizontal extents of the shape that are shared by the whole band.
+fn band_extents(edges: &[Edge], top: f64, bottom: f64) -> Option<(f64, f64)> {
+    let mut left = f64::MIN;
+    let mut right = f64::MAX;
+    for &y in &[top, (top + bottom) / 2.0, bottom] {
+        let (l, r) = scanline_extents(edges, y)?;
+        left = left.max(l);
+        right = right.min(r);
+    }
+
+    if right > left {
+        Some((left, right))
+    } else {
+        None
+    }
+}
+
+/// Returns the leftmost and the rightmost shape intersections with a horizontal line.
+fn scanline_extents(edges: &[Edge], y: f64) -> Option<(f64, f64)> {
+    let mut left = f64::MAX;
+    let mut right = f64::MIN;
+    for edge in edges {
+        let (min_y, max_y) = if edge.y1 < edge.y2 { (edge.y1, edge.y2) } else { (edge.y2, edge.y1) };
+        if y < min_y || y > max_y || min_y.fuzzy_eq(&max_y) {
+            continue;
+        }
+
+        let x = edge.x1 + (y - edge.y1) * (edge.x2 - edge.x1) / (edge.y2 - edge.y1);
+        left = left.min(x);
+        right = right.max(x);
+    }
+
+    if right > left {
+        Some((left, right))
+    } else {
+        None
+    }
+}
@@ -23,8 +23,15 @@ pub fn convert(
         return;
     }
 
+    let mut use_state = state.clone();
+    use_state.context_element = Some((
+        super::style::resolve_fill(node, true, state, tree),
+        super::style::resolve_stroke(node, true, state, tree),
+    ));
+    let state = &use_state;
+
     // We require an original transformation to setup 'clipPath'.
-    let mut orig_ts: tree::Transform = node.attribute(AId::Transform).unwrap_or_default();
+    let mut orig_ts = super::resolve_transform(node, state);
     let mut new_ts = tree::Transform::default();
 
     {
@@ -43,16 +50,21 @@ pub fn convert(
         if let Some(clip_rect) = get_clip_rect(node, child, state) {
             let mut g = clip_element(node, clip_rect, orig_ts, parent, tree);
             convert_children(child, new_ts, state, &mut g, tree);
+            super::apply_fill_box_origin(node, state, tree::Transform::default(), &mut g);
             return;
         }
     }
 
     orig_ts.append(&new_ts);
 
-    if linked_to_symbol {
-        convert_children(child, orig_ts, state, parent, tree);
+    let g = if linked_to_symbol {
+        convert_children(child, orig_ts, state, parent, tree)
     } else {
-        convert_children(node, orig_ts, state, parent, tree);
+        convert_children(node, orig_ts, state, parent, tree)
+    };
+
+    if let Some(mut g) = g {
+        super::apply_fill_box_origin(node, state, new_ts, &mut g);
     }
 }
 
@@ -63,7 +75,7 @@ pub fn convert_svg(
     tree: &mut tree::Tree,
 ) {
     // We require original transformation to setup 'clipPath'.
-    let mut orig_ts: tree::Transform = node.attribute(AId::Transform).unwrap_or_default();
+    let mut orig_ts = super::resolve_transform(node, state);
     let mut new_ts = tree::Transform::default();
 
     {
@@ -133,26 +145,27 @@ fn clip_element(
     }))
 }
 
+/// Returns a new group, if it was created.
 fn convert_children(
     node: svgtree::Node,
     transform: tree::Transform,
     state: &State,
     parent: &mut tree::Node,
     tree: &mut tree::Tree,
-) {
+) -> Option<tree::Node> {
     let required = !transform.is_default();
-    let mut parent = match super::convert_group(node, state, required, parent, tree) {
+    let (mut parent, new_group) = match super::convert_group(node, state, required, parent, tree) {
         super::GroupKind::Create(mut g) => {
             if let tree::NodeKind::Group(ref mut g) = *g.borrow_mut() {
                 g.transform = transform;
             }
 
-            g.clone()
+            (g.clone(), Some(g))
         }
         super::GroupKind::Skip => {
-            parent.clone()
+            (parent.clone(), None)
         }
-        super::GroupKind::Ignore => return,
+        super::GroupKind::Ignore => return None,
     };
 
     if state.parent_clip_path.is_some() {
@@ -160,6 +173,8 @@ fn convert_children(
     } else {
         super::convert_children(node, state, &mut parent, tree);
     }
+
+    new_group
 }
 
 fn get_clip_rect(
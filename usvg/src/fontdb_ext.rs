@@ -20,56 +20,16 @@ impl DatabaseExt for Database {
             // `ttf_parser` will check this for us.
             let units_per_em = font.units_per_em()?;
 
-            let ascent = font.ascender();
-            let descent = font.descender();
-
-            let x_height = match font.x_height() {
-                Some(height) => height,
-                None => {
-                    // If not set - fallback to height * 45%.
-                    // 45% is what Firefox uses.
-                    (f32::from(ascent - descent) * 0.45) as i16
-                }
-            };
-
-            let underline = match font.underline_metrics() {
-                Some(metrics) => metrics,
-                None => {
-                    ttf_parser::LineMetrics {
-                        position: -(units_per_em as i16) / 9,
-                        thickness: units_per_em as i16 / 12,
-                    }
-                }
-            };
-
-            let line_through_position = match font.strikeout_metrics() {
-                Some(metrics) => metrics.position,
-                None => x_height / 2,
-            };
-
-            // 0.2 and 0.4 are generic offsets used by some applications (Inkscape/librsvg).
-            let mut subscript_offset = (units_per_em as f32 / 0.2).round() as i16;
-            let mut superscript_offset = (units_per_em as f32 / 0.4).round() as i16;
-            if let Some(metrics) = font.subscript_metrics() {
-                subscript_offset = metrics.y_offset;
-            }
-
-            if let Some(metrics) = font.superscript_metrics() {
-                superscript_offset = metrics.y_offset;
-            }
-
-            Some(Font {
-                id,
+            Some(Font::from_metrics(FontSource::Database(id), FontMetrics {
                 units_per_em,
-                ascent,
-                descent,
-                x_height,
-                underline_position: underline.position,
-                underline_thickness: underline.thickness,
-                line_through_position,
-                subscript_offset,
-                superscript_offset,
-            })
+                ascent: font.ascender(),
+                descent: font.descender(),
+                x_height: font.x_height(),
+                underline: font.underline_metrics(),
+                line_through_position: font.strikeout_metrics().map(|m| m.position),
+                subscript_offset: font.subscript_metrics().map(|m| m.y_offset),
+                superscript_offset: font.superscript_metrics().map(|m| m.y_offset),
+            }))
         })?
     }
 
@@ -100,9 +60,36 @@ impl DatabaseExt for Database {
 }
 
 
+/// A font source.
+#[derive(Clone, Copy, PartialEq)]
+pub enum FontSource {
+    /// A font from the fonts database.
+    Database(ID),
+    /// An SVG font defined in the current document.
+    ///
+    /// Contains an index in the document's SVG fonts list.
+    Svg(usize),
+}
+
+
+/// Raw font metrics in font units.
+///
+/// Missing values will be replaced with fallbacks by `Font::from_metrics`.
+pub struct FontMetrics {
+    pub units_per_em: u16,
+    pub ascent: i16,
+    pub descent: i16,
+    pub x_height: Option<i16>,
+    pub underline: Option<ttf_parser::LineMetrics>,
+    pub line_through_position: Option<i16>,
+    pub subscript_offset: Option<i16>,
+    pub superscript_offset: Option<i16>,
+}
+
+
 #[derive(Clone, Copy)]
 pub struct Font {
-    pub id: ID,
+    pub source: FontSource,
 
     /// Guarantee to be > 0.
     units_per_em: u16,
@@ -125,6 +112,57 @@ pub struct Font {
 }
 
 impl Font {
+    /// Creates a new font from metrics.
+    ///
+    /// `units_per_em` must be > 0.
+    pub fn from_metrics(source: FontSource, metrics: FontMetrics) -> Self {
+        let units_per_em = metrics.units_per_em;
+        debug_assert!(units_per_em > 0);
+
+        let ascent = metrics.ascent;
+        let descent = metrics.descent;
+
+        let x_height = match metrics.x_height {
+            Some(height) => height,
+            None => {
+                // If not set - fallback to height * 45%.
+                // 45% is what Firefox uses.
+                ((i32::from(ascent) - i32::from(descent)) as f32 * 0.45) as i16
+            }
+        };
+
+        let underline = match metrics.underline {
+            Some(metrics) => metrics,
+            None => {
+                ttf_parser::LineMetrics {
+                    position: -(units_per_em as i16) / 9,
+                    thickness: units_per_em as i16 / 12,
+                }
+            }
+        };
+
+        let line_through_position = metrics.line_through_position.unwrap_or(x_height / 2);
+
+        // 0.2 and 0.4 are generic offsets used by some applications (Inkscape/librsvg).
+        let subscript_offset = metrics.subscript_offset
+            .unwrap_or_else(|| (units_per_em as f32 / 0.2).round() as i16);
+        let superscript_offset = metrics.superscript_offset
+            .unwrap_or_else(|| (units_per_em as f32 / 0.4).round() as i16);
+
+        Font {
+            source,
+            units_per_em,
+            ascent,
+            descent,
+            x_height,
+            underline_position: underline.position,
+            underline_thickness: underline.thickness,
+            line_through_position,
+            subscript_offset,
+            superscript_offset,
+        }
+    }
+
     #[inline]
     pub fn scale(&self, font_size: f64) -> f64 {
         font_size / self.units_per_em as f64
@@ -171,6 +171,7 @@ macro_rules! matches {
 }
 
 pub mod utils;
+mod color_profile;
 mod convert;
 mod error;
 mod geom;
@@ -189,6 +190,7 @@ mod short {
 pub use xmlwriter::Options as XmlOptions;
 pub use xmlwriter::Indent as XmlIndent;
 
+pub use crate::color_profile::ColorProfile;
 pub use crate::error::*;
 pub use crate::geom::*;
 pub use crate::options::*;
@@ -46,12 +46,37 @@ impl FitTo {
 }
 
 
+/// A fallback for documents without an explicit size.
+///
+/// Used when the root `svg` element has a relative `width` and/or `height`
+/// (including when they are not set at all) and doesn't have a `viewBox`.
+#[derive(Clone, Copy, Debug)]
+pub enum SizeFallback {
+    /// Use the bounding box of the document content.
+    ///
+    /// The bounding box is calculated after conversion and includes strokes.
+    ContentBBox,
+    /// Resolve relative lengths against the specified size.
+    Fixed(Size),
+    /// Return `Error::InvalidSize`.
+    Error,
+}
+
+impl Default for SizeFallback {
+    #[inline]
+    fn default() -> Self {
+        SizeFallback::ContentBBox
+    }
+}
+
+
 /// Processing options.
 #[derive(Clone, Debug)]
 pub struct Options {
     /// SVG image path.
     ///
-    /// Used to resolve relative image paths.
+    /// Used to resolve relative image paths, external style sheets and external `use` references.
+    /// Relative style sheet paths and external `use` references are ignored when not set.
     ///
     /// Default: `None`
     pub path: Option<PathBuf>,
@@ -115,6 +140,47 @@ pub struct Options {
     /// Default: false
     pub keep_named_groups: bool,
 
+    /// Specifies how the document size should be resolved
+    /// when it cannot be determined from the root element.
+    ///
+    /// Default: ContentBBox
+    pub size_fallback: SizeFallback,
+
+    /// A viewport size.
+    ///
+    /// Will be used to evaluate CSS `@media` queries.
+    /// When not set, `width` and `height` related media features will not match.
+    ///
+    /// Default: None
+    pub viewport_size: Option<Size>,
+
+    /// Allows loading external style sheets.
+    ///
+    /// Affects `xml-stylesheet` processing instructions and CSS `@import` rules.
+    /// Relative paths are resolved relative to `path` and are ignored when it's not set.
+    ///
+    /// Default: false
+    pub load_external_stylesheets: bool,
+
+    /// Allows loading external SVG files referenced by `use` elements.
+    ///
+    /// Requires `path` to be set.
+    ///
+    /// Default: false
+    pub load_external_use: bool,
+
+    /// A user style sheet.
+    ///
+    /// Will be applied on top of the document styles,
+    /// so its declarations take precedence over the document ones,
+    /// including presentation attributes and `style` attributes.
+    /// Only `!important` document declarations cannot be overridden.
+    ///
+    /// `@import` rules are not supported.
+    ///
+    /// Default: None
+    pub user_stylesheet: Option<String>,
+
     /// When empty, `text` elements will be skipped.
     ///
     /// Default: empty
@@ -147,6 +213,11 @@ impl Default for Options {
             text_rendering: TextRendering::default(),
             image_rendering: ImageRendering::default(),
             keep_named_groups: false,
+            size_fallback: SizeFallback::default(),
+            viewport_size: None,
+            load_external_stylesheets: false,
+            load_external_use: false,
+            user_stylesheet: None,
             #[cfg(feature = "text")]
             fontdb: fontdb::Database::new(),
         }
@@ -0,0 +1,625 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A CSS cascade implementation.
+//!
+//! `simplecss` is used only to parse selectors and declarations,
+//! while rules collecting, at-rules and declarations ordering are done here.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use log::warn;
+
+use crate::Options;
+
+
+/// A style sheet origin.
+///
+/// User style sheets take precedence over the author ones.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Origin {
+    Author,
+    User,
+}
+
+/// A style rule with a single selector.
+pub struct Rule<'a> {
+    pub origin: Origin,
+    pub selector: simplecss::Selector<'a>,
+    pub specificity: [u8; 3],
+    pub declarations: Vec<simplecss::Declaration<'a>>,
+}
+
+/// A list of style rules in the document order.
+pub struct StyleSheet<'a> {
+    pub rules: Vec<Rule<'a>>,
+}
+
+impl<'a> StyleSheet<'a> {
+    /// Creates an empty style sheet.
+    pub fn new() -> Self {
+        StyleSheet { rules: Vec::new() }
+    }
+
+    /// Parses a CSS text and appends its rules to the style sheet.
+    ///
+    /// `@media` rules are evaluated using `Options::viewport_size` and `Options::dpi`.
+    /// `@import` rules are ignored, since they must be resolved
+    /// beforehand via `collect_imports`. All other at-rules are ignored too.
+    pub fn parse_more(&mut self, text: &'a str, origin: Origin, opt: &Options) {
+        let mut s = Stream { text, pos: 0 };
+        loop {
+            s.skip_spaces_and_comments();
+            match s.curr_byte() {
+                Some(b'@') => self.consume_at_rule(&mut s, origin, opt),
+                Some(_) => self.consume_rule_set(&mut s, origin),
+                None => break,
+            }
+        }
+    }
+
+    fn consume_at_rule(&mut self, s: &mut Stream<'a>, origin: Origin, opt: &Options) {
+        s.advance(1); // @
+        let name = s.consume_ident();
+        let prelude = s.consume_until(b";{");
+        match s.curr_byte() {
+            Some(b'{') => {
+                let block = s.consume_block();
+                if name.eq_ignore_ascii_case("media") {
+                    if media_query_list_matches(prelude, opt) {
+                        self.parse_more(block, origin, opt);
+                    }
+                } else {
+                    warn!("The '@{}' rule is not supported. Skipped.", name);
+                }
+            }
+            Some(b';') => {
+                s.advance(1);
+                if !name.eq_ignore_ascii_case("import") && !name.eq_ignore_ascii_case("charset") {
+                    warn!("The '@{}' rule is not supported. Skipped.", name);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn consume_rule_set(&mut self, s: &mut Stream<'a>, origin: Origin) {
+        let selectors = s.consume_until(b"{");
+        if s.curr_byte().is_none() {
+            warn!("A CSS rule without a declaration block. Skipped.");
+            return;
+        }
+
+        let block = s.consume_block();
+        let declarations: Vec<_> = simplecss::DeclarationTokenizer::from(block).collect();
+        if declarations.is_empty() {
+            return;
+        }
+
+        for text in split_top_level(selectors, b',') {
+            let text = text.trim();
+            if let Some(selector) = simplecss::Selector::parse(text) {
+                self.rules.push(Rule {
+                    origin,
+                    selector,
+                    specificity: calc_specificity(text),
+                    declarations: declarations.clone(),
+                });
+            }
+        }
+    }
+
+    /// Collects all declarations that apply to the element.
+    ///
+    /// Declarations from the `style` attribute are treated as inline ones.
+    /// User declarations take precedence over author ones, including inline.
+    ///
+    /// The returned list is sorted in the cascade order,
+    /// so the declarations that take precedence are at the end.
+    pub fn collect_declarations<E: simplecss::Element>(
+        &self,
+        element: &E,
+        style: Option<&'a str>,
+    ) -> Vec<simplecss::Declaration<'a>> {
+        let mut list = Vec::new();
+        for (idx, rule) in self.rules.iter().enumerate() {
+            if rule.selector.matches(element) {
+                for declaration in &rule.declarations {
+                    let key = CascadeKey {
+                        important: declaration.important,
+                        origin: rule.origin,
+                        inline: false,
+                        specificity: rule.specificity,
+                        order: idx,
+                    };
+                    list.push((key, *declaration));
+                }
+            }
+        }
+
+        if let Some(style) = style {
+            for declaration in simplecss::DeclarationTokenizer::from(style) {
+                let key = CascadeKey {
+                    important: declaration.important,
+                    origin: Origin::Author,
+                    inline: true,
+                    specificity: [0, 0, 0],
+                    order: 0,
+                };
+                list.push((key, declaration));
+            }
+        }
+
+        // A stable sort is required, since declarations inside a single rule
+        // must preserve their order.
+        list.sort_by_key(|(key, _)| *key);
+        list.into_iter().map(|(_, d)| d).collect()
+    }
+}
+
+/// A declaration position in the cascade.
+///
+/// Fields order matters, since it defines the comparison order.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct CascadeKey {
+    important: bool,
+    origin: Origin,
+    inline: bool,
+    specificity: [u8; 3],
+    order: usize,
+}
+
+fn calc_specificity(selector: &str) -> [u8; 3] {
+    use simplecss::SelectorToken;
+
+    let mut spec = [0u8; 3];
+    for token in simplecss::SelectorTokenizer::from(selector) {
+        let idx = match token {
+            Ok(SelectorToken::IdSelector(_)) => 0,
+            Ok(SelectorToken::ClassSelector(_)) |
+            Ok(SelectorToken::AttributeSelector(..)) |
+            Ok(SelectorToken::PseudoClass(_)) |
+            Ok(SelectorToken::LangPseudoClass(_)) => 1,
+            Ok(SelectorToken::TypeSelector(_)) => 2,
+            Ok(_) => continue,
+            Err(_) => break,
+        };
+
+        spec[idx] = spec[idx].saturating_add(1);
+    }
+
+    spec
+}
+
+/// Splits a text by a separator, ignoring the ones inside strings and brackets.
+fn split_top_level(text: &str, sep: u8) -> Vec<&str> {
+    let mut list = Vec::new();
+    let mut s = Stream { text, pos: 0 };
+    loop {
+        list.push(s.consume_until(&[sep]));
+        if s.curr_byte().is_none() {
+            break;
+        }
+
+        s.advance(1);
+    }
+
+    list
+}
+
+/// Loads style sheets referenced by `@import` rules.
+///
+/// Imported style sheets are appended to `sources` before the importing one,
+/// since `@import` rules must precede all other rules.
+///
+/// `dir` is a directory used to resolve relative paths.
+/// `stack` contains paths of the style sheets that are currently being imported
+/// and is used to detect cycles.
+pub fn collect_imports<'a>(
+    text: &str,
+    dir: Option<&Path>,
+    opt: &Options,
+    stack: &mut Vec<PathBuf>,
+    sources: &mut Vec<Cow<'a, str>>,
+) {
+    let mut s = Stream { text, pos: 0 };
+    loop {
+        s.skip_spaces_and_comments();
+        if s.curr_byte() != Some(b'@') {
+            break;
+        }
+
+        s.advance(1); // @
+        let name = s.consume_ident();
+        let prelude = s.consume_until(b";{");
+        if s.curr_byte() != Some(b';') {
+            break;
+        }
+        s.advance(1);
+
+        if name.eq_ignore_ascii_case("charset") {
+            continue;
+        } else if !name.eq_ignore_ascii_case("import") {
+            // `@import` rules after any other rule must be ignored.
+            break;
+        }
+
+        let (href, media) = match parse_import(prelude) {
+            Some(v) => v,
+            None => {
+                warn!("Invalid '@import' rule: '{}'. Skipped.", prelude.trim());
+                continue;
+            }
+        };
+
+        if !media_query_list_matches(media, opt) {
+            continue;
+        }
+
+        if let Some(path) = resolve_path(href, dir) {
+            load_file(&path, opt, stack, sources);
+        }
+    }
+}
+
+/// Loads a style sheet file and all the style sheets it imports.
+pub fn load_file<'a>(
+    path: &Path,
+    opt: &Options,
+    stack: &mut Vec<PathBuf>,
+    sources: &mut Vec<Cow<'a, str>>,
+) {
+    let path = match path.canonicalize() {
+        Ok(path) => path,
+        Err(_) => {
+            warn!("Failed to load '{}'. Skipped.", path.display());
+            return;
+        }
+    };
+
+    if stack.contains(&path) {
+        warn!("'{}' has a recursive import. Skipped.", path.display());
+        return;
+    }
+
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(_) => {
+            warn!("Failed to load '{}'. Skipped.", path.display());
+            return;
+        }
+    };
+
+    stack.push(path.clone());
+    collect_imports(&text, path.parent(), opt, stack, sources);
+    stack.pop();
+
+    sources.push(Cow::Owned(text));
+}
+
+/// Resolves a style sheet path relative to the `dir`.
+///
+/// Relative paths cannot be resolved without the document path,
+/// otherwise they will be resolved relative to the working directory.
+pub fn resolve_path(href: &str, dir: Option<&Path>) -> Option<PathBuf> {
+    let path = Path::new(href);
+    if path.is_absolute() {
+        return Some(path.to_path_buf());
+    }
+
+    match dir {
+        Some(dir) => Some(dir.join(path)),
+        None => {
+            warn!("'{}' cannot be loaded without `Options::path`. Skipped.", href);
+            None
+        }
+    }
+}
+
+/// Parses an `@import` rule prelude into a URL and a media query list.
+fn parse_import(prelude: &str) -> Option<(&str, &str)> {
+    let prelude = prelude.trim();
+    let (href, media) = if prelude.starts_with("url(") {
+        let end = prelude.find(')')?;
+        (prelude[4..end].trim(), &prelude[end + 1..])
+    } else {
+        let quote = prelude.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+
+        let end = prelude[1..].find(quote)? + 1;
+        (&prelude[..end + 1], &prelude[end + 1..])
+    };
+
+    let href = href.trim_matches(|c| c == '"' || c == '\'');
+    if href.is_empty() {
+        return None;
+    }
+
+    Some((href, media))
+}
+
+/// Checks that a media query list matches the current options.
+pub fn media_query_list_matches(list: &str, opt: &Options) -> bool {
+    // An empty media query list matches all.
+    if list.trim().is_empty() {
+        return true;
+    }
+
+    split_top_level(list, b',').into_iter().any(|query| media_query_matches(query, opt))
+}
+
+fn media_query_matches(query: &str, opt: &Options) -> bool {
+    let mut s = Stream { text: query, pos: 0 };
+    let mut negate = false;
+    let mut matches = true;
+    let mut is_first = true;
+    loop {
+        s.skip_spaces_and_comments();
+        match s.curr_byte() {
+            Some(b'(') => {
+                let block = s.consume_parenthesized();
+                matches &= media_feature_matches(block, opt);
+            }
+            Some(_) => {
+                let word = s.consume_ident();
+                if word.is_empty() {
+                    // Malformed queries are evaluated to `not all`.
+                    return false;
+                }
+
+                let word = word.to_ascii_lowercase();
+                match word.as_str() {
+                    "not" if is_first => negate = true,
+                    "only" if is_first => {}
+                    "and" if !is_first => {}
+                    "all" | "screen" => {}
+                    "print" | "speech" | "tv" | "projection" | "handheld"
+                    | "tty" | "braille" | "embossed" | "aural" => matches = false,
+                    _ => {
+                        warn!("Unsupported media query: '{}'.", query.trim());
+                        return false;
+                    }
+                }
+            }
+            None => break,
+        }
+
+        is_first = false;
+    }
+
+    matches != negate
+}
+
+fn media_feature_matches(feature: &str, opt: &Options) -> bool {
+    let (name, value) = match feature.find(':') {
+        Some(idx) => (feature[..idx].trim(), Some(feature[idx + 1..].trim())),
+        None => (feature.trim(), None),
+    };
+
+    let name = name.to_ascii_lowercase();
+    let (prefix, name) = if name.starts_with("min-") {
+        (Some(true), &name[4..])
+    } else if name.starts_with("max-") {
+        (Some(false), &name[4..])
+    } else {
+        (None, name.as_str())
+    };
+
+    let viewport = opt.viewport_size;
+
+    let value = match value {
+        Some(value) => value,
+        None => {
+            // A boolean context.
+            return match name {
+                "width" | "height" | "aspect-ratio" | "orientation" => viewport.is_some(),
+                "color" => true,
+                _ => false,
+            };
+        }
+    };
+
+    let compare = |actual: f64, expected: f64| {
+        match prefix {
+            Some(true) => actual >= expected,
+            Some(false) => actual <= expected,
+            None => (actual - expected).abs() < 0.001,
+        }
+    };
+
+    match name {
+        "width" | "height" => {
+            let viewport = try_opt_or!(viewport, false);
+            let expected = try_opt_or!(parse_media_length(value, opt), false);
+            let actual = if name == "width" { viewport.width() } else { viewport.height() };
+            compare(actual, expected)
+        }
+        "aspect-ratio" => {
+            let viewport = try_opt_or!(viewport, false);
+            let expected = try_opt_or!(parse_ratio(value), false);
+            compare(viewport.width() / viewport.height(), expected)
+        }
+        "orientation" if prefix.is_none() => {
+            let viewport = try_opt_or!(viewport, false);
+            let is_portrait = viewport.height() >= viewport.width();
+            match value {
+                "portrait" => is_portrait,
+                "landscape" => !is_portrait,
+                _ => false,
+            }
+        }
+        "color" => true,
+        "prefers-color-scheme" if prefix.is_none() => value == "light",
+        _ => {
+            warn!("Unsupported media feature: '{}'.", feature.trim());
+            false
+        }
+    }
+}
+
+/// Parses a media feature length and converts it into pixels.
+fn parse_media_length(value: &str, opt: &Options) -> Option<f64> {
+    use svgtypes::LengthUnit as Unit;
+
+    let length = svgtypes::Length::from_str(value).ok()?;
+    let n = length.num;
+    let px = match length.unit {
+        Unit::None | Unit::Px => n,
+        Unit::Em => n * 16.0,
+        Unit::Ex => n * 8.0,
+        Unit::In => n * opt.dpi,
+        Unit::Cm => n * opt.dpi / 2.54,
+        Unit::Mm => n * opt.dpi / 25.4,
+        Unit::Pt => n * opt.dpi / 72.0,
+        Unit::Pc => n * opt.dpi / 6.0,
+        Unit::Percent => return None,
+    };
+
+    Some(px)
+}
+
+fn parse_ratio(value: &str) -> Option<f64> {
+    let mut iter = value.splitn(2, '/');
+    let a = f64::from_str(iter.next()?.trim()).ok()?;
+    let b = match iter.next() {
+        Some(b) => f64::from_str(b.trim()).ok()?,
+        None => 1.0,
+    };
+
+    if a.is_finite() && b.is_finite() && b > 0.0 {
+        Some(a / b)
+    } else {
+        None
+    }
+}
+
+
+struct Stream<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Stream<'a> {
+    fn curr_byte(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).cloned()
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.pos = std::cmp::min(self.pos + n, self.text.len());
+    }
+
+    fn starts_with(&self, prefix: &[u8]) -> bool {
+        self.text.as_bytes()[self.pos..].starts_with(prefix)
+    }
+
+    fn skip_spaces_and_comments(&mut self) {
+        loop {
+            while let Some(c) = self.curr_byte() {
+                if c.is_ascii_whitespace() {
+                    self.advance(1);
+                } else {
+                    break;
+                }
+            }
+
+            if self.starts_with(b"/*") {
+                self.skip_comment();
+            } else if self.starts_with(b"<!--") {
+                self.advance(4);
+            } else if self.starts_with(b"-->") {
+                self.advance(3);
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        match self.text[self.pos + 2..].find("*/") {
+            Some(idx) => self.advance(idx + 4),
+            None => self.pos = self.text.len(),
+        }
+    }
+
+    fn skip_string(&mut self) {
+        let quote = self.curr_byte();
+        self.advance(1);
+        while let Some(c) = self.curr_byte() {
+            self.advance(1);
+            if c == b'\\' {
+                self.advance(1);
+            } else if Some(c) == quote {
+                break;
+            }
+        }
+    }
+
+    fn consume_ident(&mut self) -> &'a str {
+        let start = self.pos;
+        while let Some(c) = self.curr_byte() {
+            if c.is_ascii_alphanumeric() || c == b'-' || c == b'_' {
+                self.advance(1);
+            } else {
+                break;
+            }
+        }
+
+        &self.text[start..self.pos]
+    }
+
+    /// Consumes a text until one of the `stop` bytes at the top level.
+    ///
+    /// Strings, comments and bracketed blocks are skipped as a whole.
+    fn consume_until(&mut self, stop: &[u8]) -> &'a str {
+        let start = self.pos;
+        let mut depth = 0usize;
+        while let Some(c) = self.curr_byte() {
+            if depth == 0 && stop.contains(&c) {
+                break;
+            }
+
+            match c {
+                b'"' | b'\'' => {
+                    self.skip_string();
+                    continue;
+                }
+                b'/' if self.starts_with(b"/*") => {
+                    self.skip_comment();
+                    continue;
+                }
+                b'{' | b'(' | b'[' => depth += 1,
+                b'}' | b')' | b']' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+
+            self.advance(1);
+        }
+
+        &self.text[start..self.pos]
+    }
+
+    /// Consumes a `{...}` block and returns its content.
+    fn consume_block(&mut self) -> &'a str {
+        debug_assert_eq!(self.curr_byte(), Some(b'{'));
+
+        self.advance(1);
+        let content = self.consume_until(b"}");
+        self.advance(1);
+        content
+    }
+
+    /// Consumes a `(...)` block and returns its content.
+    fn consume_parenthesized(&mut self) -> &'a str {
+        debug_assert_eq!(self.curr_byte(), Some(b'('));
+
+        self.advance(1);
+        let content = self.consume_until(b")");
+        self.advance(1);
+        content
+    }
+}
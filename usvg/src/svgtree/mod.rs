@@ -12,6 +12,7 @@ use svgtypes::FuzzyEq;
 use crate::geom::Rect;
 use crate::tree;
 
+mod css;
 mod parse;
 pub use parse::*;
 
@@ -140,22 +141,70 @@ struct NodeData {
 pub enum AttributeValue {
     None,
     CurrentColor,
+    ContextFill,
+    ContextStroke,
     Angle(svgtypes::Angle),
     AspectRatio(svgtypes::AspectRatio),
     Color(svgtypes::Color),
     EnableBackground(tree::EnableBackground),
+    /// A `filter` list with more than one item or with filter functions.
+    ///
+    /// A single `url()` is stored as `Link`.
+    Filters(Vec<FilterValue>),
     Length(svgtypes::Length),
     Link(String),
     Number(f64),
     NumberList(svgtypes::NumberList),
     Opacity(tree::Opacity),
     Paint(String, Option<svgtypes::PaintFallback>),
+    PaintOrder(tree::PaintOrder),
     Path(tree::SharedPathData),
     String(String),
     Transform(svgtypes::Transform),
+    TransformOrigin(TransformOrigin),
     ViewBox(svgtypes::ViewBox),
 }
 
+/// A parsed `transform-origin` value.
+///
+/// Keywords are stored as percentages and the `z` component is ignored.
+#[derive(Clone, Copy, Debug)]
+pub struct TransformOrigin {
+    pub x: svgtypes::Length,
+    pub y: svgtypes::Length,
+}
+
+/// A `filter` list item.
+#[derive(Clone, Debug)]
+pub enum FilterValue {
+    Url(String),
+    Function(FilterFunction),
+}
+
+/// A CSS filter function.
+///
+/// Amounts are already validated and clamped.
+#[derive(Clone, Copy, Debug)]
+pub enum FilterFunction {
+    Blur(svgtypes::Length),
+    Brightness(f64),
+    Contrast(f64),
+    DropShadow {
+        /// `None` indicates `currentColor`.
+        color: Option<svgtypes::Color>,
+        dx: svgtypes::Length,
+        dy: svgtypes::Length,
+        std_dev: svgtypes::Length,
+    },
+    Grayscale(f64),
+    /// An angle in degrees.
+    HueRotate(f64),
+    Invert(f64),
+    Opacity(f64),
+    Saturate(f64),
+    Sepia(f64),
+}
+
 #[derive(Clone)]
 pub struct Attribute {
     pub name: AId,
@@ -616,6 +665,18 @@ impl_from_value!(svgtypes::Angle, Angle);
 impl_from_value!(f64, Number);
 impl_from_value!(tree::Opacity, Opacity);
 impl_from_value!(tree::EnableBackground, EnableBackground);
+impl_from_value!(tree::PaintOrder, PaintOrder);
+impl_from_value!(TransformOrigin, TransformOrigin);
+
+impl<'a> FromValue<'a> for &'a [FilterValue] {
+    fn get(node: Node<'a>, aid: AId) -> Option<Self> {
+        let a = node.attributes().iter().find(|a| a.name == aid)?;
+        match a.value {
+            AttributeValue::Filters(ref list) => Some(list),
+            _ => None,
+        }
+    }
+}
 
 impl<'a> FromValue<'a> for &'a AttributeValue {
     fn get(node: Node<'a>, aid: AId) -> Option<Self> {
@@ -729,7 +790,9 @@ impl EId {
         matches!(self,
               EId::LinearGradient
             | EId::RadialGradient
+            | EId::Meshgradient
             | EId::Pattern
+            | EId::Hatch
         )
     }
 }
@@ -737,13 +800,16 @@ impl EId {
 impl AId {
     pub fn is_presentation(&self) -> bool {
         matches!(self,
-              AId::BaselineShift
+              AId::AlignmentBaseline
+            | AId::BaselineShift
             | AId::ClipPath
             | AId::ClipRule
             | AId::Color
+            | AId::ColorInterpolation
             | AId::ColorInterpolationFilters
             | AId::Direction
             | AId::Display
+            | AId::DominantBaseline
             | AId::Fill
             | AId::FillOpacity
             | AId::FillRule
@@ -751,19 +817,29 @@ impl AId {
             | AId::FloodColor
             | AId::FloodOpacity
             | AId::FontFamily
+            | AId::FontFeatureSettings
             | AId::FontSize
             | AId::FontStretch
             | AId::FontStyle
             | AId::FontVariant
             | AId::FontWeight
             | AId::ImageRendering
+            | AId::InlineSize
+            | AId::Isolation
+            | AId::Kerning
             | AId::LetterSpacing
             | AId::MarkerEnd
             | AId::MarkerMid
             | AId::MarkerStart
             | AId::Mask
+            | AId::MaskMode
+            | AId::MaskType
+            | AId::MixBlendMode
             | AId::Opacity
             | AId::Overflow
+            | AId::PaintOrder
+            | AId::ShapeInside
+            | AId::ShapePadding
             | AId::ShapeRendering
             | AId::StopColor
             | AId::StopOpacity
@@ -778,7 +854,12 @@ impl AId {
             | AId::TextAnchor
             | AId::TextDecoration
             | AId::TextRendering
+            | AId::TransformBox
+            | AId::TransformOrigin
+            | AId::UnicodeBidi
+            | AId::VectorEffect
             | AId::Visibility
+            | AId::WhiteSpace
             | AId::WordSpacing
             | AId::WritingMode)
     }
@@ -793,13 +874,16 @@ impl AId {
 
     pub fn allows_inherit_value(&self) -> bool {
         matches!(self,
-              AId::BaselineShift
+              AId::AlignmentBaseline
+            | AId::BaselineShift
             | AId::ClipPath
             | AId::ClipRule
             | AId::Color
+            | AId::ColorInterpolation
             | AId::ColorInterpolationFilters
             | AId::Direction
             | AId::Display
+            | AId::DominantBaseline
             | AId::Fill
             | AId::FillOpacity
             | AId::FillRule
@@ -807,19 +891,29 @@ impl AId {
             | AId::FloodColor
             | AId::FloodOpacity
             | AId::FontFamily
+            | AId::FontFeatureSettings
             | AId::FontSize
             | AId::FontStretch
             | AId::FontStyle
             | AId::FontVariant
             | AId::FontWeight
             | AId::ImageRendering
+            | AId::InlineSize
+            | AId::Isolation
+            | AId::Kerning
             | AId::LetterSpacing
             | AId::MarkerEnd
             | AId::MarkerMid
             | AId::MarkerStart
             | AId::Mask
+            | AId::MaskMode
+            | AId::MaskType
+            | AId::MixBlendMode
             | AId::Opacity
             | AId::Overflow
+            | AId::PaintOrder
+            | AId::ShapeInside
+            | AId::ShapePadding
             | AId::ShapeRendering
             | AId::StopColor
             | AId::StopOpacity
@@ -834,7 +928,12 @@ impl AId {
             | AId::TextAnchor
             | AId::TextDecoration
             | AId::TextRendering
+            | AId::TransformBox
+            | AId::TransformOrigin
+            | AId::UnicodeBidi
+            | AId::VectorEffect
             | AId::Visibility
+            | AId::WhiteSpace
             | AId::WordSpacing
             | AId::WritingMode)
     }
@@ -842,16 +941,28 @@ impl AId {
 
 fn is_non_inheritable(id: AId) -> bool {
     matches!(id,
-          AId::BaselineShift
+          AId::AlignmentBaseline
+        | AId::BaselineShift
         | AId::ClipPath
         | AId::Display
         | AId::Filter
         | AId::FloodColor
         | AId::FloodOpacity
+        | AId::InlineSize
+        | AId::Isolation
         | AId::Mask
+        | AId::MaskMode
+        | AId::MaskType
+        | AId::MixBlendMode
         | AId::Opacity
         | AId::Overflow
+        | AId::ShapeInside
+        | AId::ShapePadding
         | AId::StopColor
         | AId::StopOpacity
-        | AId::TextDecoration)
+        | AId::TextDecoration
+        | AId::TransformBox
+        | AId::TransformOrigin
+        | AId::UnicodeBidi
+        | AId::VectorEffect)
 }
@@ -18,6 +18,7 @@ pub enum EId {
     FeDiffuseLighting,
     FeDisplacementMap,
     FeDistantLight,
+    FeDropShadow,
     FeFlood,
     FeFuncA,
     FeFuncB,
@@ -35,12 +36,22 @@ pub enum EId {
     FeTile,
     FeTurbulence,
     Filter,
+    Font,
+    FontFace,
     G,
+    Glyph,
+    Hatch,
+    Hatchpath,
+    Hkern,
     Image,
     Line,
     LinearGradient,
     Marker,
     Mask,
+    Meshgradient,
+    Meshpatch,
+    Meshrow,
+    MissingGlyph,
     Path,
     Pattern,
     Polygon,
@@ -60,73 +71,86 @@ pub enum EId {
 }
 
 static ELEMENTS: Map<EId> = Map {
-    key: 3558916427560184125,
+    key: 3213172566270843353,
     disps: &[
-        (1, 13),
-        (5, 3),
-        (0, 19),
-        (6, 24),
-        (0, 0),
+        (3, 59),
+        (0, 18),
         (0, 3),
-        (0, 1),
-        (11, 10),
-        (0, 21),
         (0, 0),
-        (5, 0),
+        (46, 58),
+        (1, 33),
+        (23, 25),
+        (18, 9),
+        (3, 50),
+        (0, 2),
+        (2, 0),
+        (2, 22),
+        (2, 14),
     ],
     entries: &[
-        ("feConvolveMatrix", EId::FeConvolveMatrix),
-        ("tspan", EId::Tspan),
-        ("style", EId::Style),
-        ("feFuncB", EId::FeFuncB),
-        ("rect", EId::Rect),
-        ("marker", EId::Marker),
-        ("feDiffuseLighting", EId::FeDiffuseLighting),
-        ("g", EId::G),
         ("symbol", EId::Symbol),
-        ("pattern", EId::Pattern),
-        ("path", EId::Path),
-        ("feFuncR", EId::FeFuncR),
-        ("a", EId::A),
         ("textPath", EId::TextPath),
+        ("hatch", EId::Hatch),
+        ("svg", EId::Svg),
+        ("feDistantLight", EId::FeDistantLight),
+        ("feDiffuseLighting", EId::FeDiffuseLighting),
+        ("style", EId::Style),
+        ("feMerge", EId::FeMerge),
+        ("font", EId::Font),
+        ("filter", EId::Filter),
+        ("polygon", EId::Polygon),
+        ("stop", EId::Stop),
+        ("feTile", EId::FeTile),
+        ("meshpatch", EId::Meshpatch),
+        ("font-face", EId::FontFace),
+        ("glyph", EId::Glyph),
+        ("feDisplacementMap", EId::FeDisplacementMap),
         ("use", EId::Use),
-        ("feFuncA", EId::FeFuncA),
-        ("tref", EId::Tref),
-        ("circle", EId::Circle),
-        ("fePointLight", EId::FePointLight),
+        ("feOffset", EId::FeOffset),
+        ("marker", EId::Marker),
+        ("clipPath", EId::ClipPath),
         ("defs", EId::Defs),
-        ("feTile", EId::FeTile),
-        ("image", EId::Image),
-        ("stop", EId::Stop),
+        ("feImage", EId::FeImage),
+        ("hkern", EId::Hkern),
+        ("g", EId::G),
+        ("feConvolveMatrix", EId::FeConvolveMatrix),
         ("feGaussianBlur", EId::FeGaussianBlur),
+        ("feFuncG", EId::FeFuncG),
         ("feFlood", EId::FeFlood),
+        ("text", EId::Text),
+        ("pattern", EId::Pattern),
+        ("path", EId::Path),
         ("polyline", EId::Polyline),
-        ("feComponentTransfer", EId::FeComponentTransfer),
+        ("meshgradient", EId::Meshgradient),
+        ("switch", EId::Switch),
         ("linearGradient", EId::LinearGradient),
-        ("feFuncG", EId::FeFuncG),
-        ("ellipse", EId::Ellipse),
-        ("clipPath", EId::ClipPath),
-        ("feMerge", EId::FeMerge),
-        ("feSpotLight", EId::FeSpotLight),
-        ("feBlend", EId::FeBlend),
-        ("svg", EId::Svg),
+        ("feComponentTransfer", EId::FeComponentTransfer),
         ("feColorMatrix", EId::FeColorMatrix),
-        ("feTurbulence", EId::FeTurbulence),
-        ("feSpecularLighting", EId::FeSpecularLighting),
-        ("switch", EId::Switch),
-        ("feMorphology", EId::FeMorphology),
-        ("feImage", EId::FeImage),
-        ("feMergeNode", EId::FeMergeNode),
-        ("feOffset", EId::FeOffset),
-        ("polygon", EId::Polygon),
-        ("feComposite", EId::FeComposite),
         ("radialGradient", EId::RadialGradient),
         ("line", EId::Line),
-        ("feDisplacementMap", EId::FeDisplacementMap),
-        ("feDistantLight", EId::FeDistantLight),
+        ("image", EId::Image),
+        ("feFuncA", EId::FeFuncA),
+        ("feSpotLight", EId::FeSpotLight),
+        ("feDropShadow", EId::FeDropShadow),
+        ("feComposite", EId::FeComposite),
+        ("a", EId::A),
+        ("feFuncR", EId::FeFuncR),
         ("mask", EId::Mask),
-        ("text", EId::Text),
-        ("filter", EId::Filter),
+        ("hatchpath", EId::Hatchpath),
+        ("meshrow", EId::Meshrow),
+        ("tspan", EId::Tspan),
+        ("feFuncB", EId::FeFuncB),
+        ("ellipse", EId::Ellipse),
+        ("circle", EId::Circle),
+        ("feMorphology", EId::FeMorphology),
+        ("feTurbulence", EId::FeTurbulence),
+        ("feSpecularLighting", EId::FeSpecularLighting),
+        ("fePointLight", EId::FePointLight),
+        ("missing-glyph", EId::MissingGlyph),
+        ("feBlend", EId::FeBlend),
+        ("tref", EId::Tref),
+        ("feMergeNode", EId::FeMergeNode),
+        ("rect", EId::Rect),
     ],
 };
 
@@ -155,7 +179,9 @@ impl fmt::Display for EId {
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum AId {
+    AlignmentBaseline,
     Amplitude,
+    Ascent,
     Azimuth,
     BaseFrequency,
     BaselineShift,
@@ -165,14 +191,17 @@ pub enum AId {
     ClipRule,
     ClipPathUnits,
     Color,
+    ColorInterpolation,
     ColorInterpolationFilters,
     Cx,
     Cy,
     D,
+    Descent,
     DiffuseConstant,
     Direction,
     Display,
     Divisor,
+    DominantBaseline,
     Dx,
     Dy,
     EdgeMode,
@@ -187,6 +216,7 @@ pub enum AId {
     FloodColor,
     FloodOpacity,
     FontFamily,
+    FontFeatureSettings,
     FontSize,
     FontStretch,
     FontStyle,
@@ -194,21 +224,32 @@ pub enum AId {
     FontWeight,
     Fx,
     Fy,
+    G1,
+    G2,
+    GlyphName,
     GradientTransform,
     GradientUnits,
+    HatchContentUnits,
+    HatchUnits,
     Height,
+    HorizAdvX,
     Href,
     Id,
     ImageRendering,
     In,
     In2,
+    InlineSize,
     Intercept,
+    Isolation,
+    K,
     K1,
     K2,
     K3,
     K4,
     KernelMatrix,
     KernelUnitLength,
+    Kerning,
+    LengthAdjust,
     LetterSpacing,
     LightingColor,
     LimitingConeAngle,
@@ -219,8 +260,11 @@ pub enum AId {
     MarkerUnits,
     MarkerWidth,
     Mask,
+    MaskMode,
+    MaskType,
     MaskContentUnits,
     MaskUnits,
+    MixBlendMode,
     Mode,
     NumOctaves,
     Offset,
@@ -229,9 +273,12 @@ pub enum AId {
     Order,
     Orient,
     Overflow,
+    PaintOrder,
+    Path,
     PatternContentUnits,
     PatternTransform,
     PatternUnits,
+    Pitch,
     Points,
     PointsAtX,
     PointsAtY,
@@ -251,6 +298,8 @@ pub enum AId {
     Ry,
     Scale,
     Seed,
+    ShapeInside,
+    ShapePadding,
     ShapeRendering,
     Slope,
     Space,
@@ -262,6 +311,7 @@ pub enum AId {
     StitchTiles,
     StopColor,
     StopOpacity,
+    StrikethroughPosition,
     Stroke,
     StrokeDasharray,
     StrokeDashoffset,
@@ -279,15 +329,28 @@ pub enum AId {
     TextAnchor,
     TextDecoration,
     TextRendering,
+    TextLength,
     Transform,
+    TransformBox,
+    TransformOrigin,
     Type,
+    U1,
+    U2,
+    UnderlinePosition,
+    UnderlineThickness,
+    Unicode,
+    UnicodeBidi,
+    UnitsPerEm,
     Values,
+    VectorEffect,
     ViewBox,
     Visibility,
+    WhiteSpace,
     Width,
     WordSpacing,
     WritingMode,
     X,
+    XHeight,
     X1,
     X2,
     XChannelSelector,
@@ -299,180 +362,226 @@ pub enum AId {
 }
 
 static ATTRIBUTES: Map<AId> = Map {
-    key: 3213172566270843353,
+    key: 3347381344252206323,
     disps: &[
-        (0, 32),
+        (0, 156),
+        (0, 83),
+        (10, 12),
+        (0, 1),
         (0, 1),
-        (0, 16),
-        (0, 31),
-        (9, 125),
-        (1, 4),
-        (5, 102),
-        (0, 44),
-        (11, 69),
-        (5, 77),
+        (0, 2),
+        (9, 178),
         (0, 20),
-        (0, 8),
-        (0, 3),
-        (0, 8),
-        (2, 43),
-        (1, 0),
+        (0, 137),
+        (9, 83),
+        (0, 14),
+        (2, 6),
         (0, 0),
-        (25, 133),
-        (12, 99),
-        (0, 3),
-        (0, 68),
-        (0, 33),
-        (1, 54),
-        (0, 64),
-        (2, 119),
-        (0, 18),
-        (0, 7),
+        (0, 0),
+        (0, 2),
+        (0, 24),
         (1, 19),
-        (9, 46),
+        (1, 89),
+        (0, 0),
+        (2, 136),
+        (0, 3),
+        (15, 24),
+        (2, 112),
+        (0, 14),
+        (32, 18),
+        (0, 132),
+        (2, 98),
+        (0, 134),
+        (37, 87),
+        (57, 167),
+        (3, 9),
+        (0, 56),
+        (0, 52),
+        (0, 10),
+        (1, 93),
+        (11, 64),
     ],
     entries: &[
-        ("pointsAtZ", AId::PointsAtZ),
-        ("stop-color", AId::StopColor),
-        ("xChannelSelector", AId::XChannelSelector),
-        ("systemLanguage", AId::SystemLanguage),
-        ("cy", AId::Cy),
-        ("dy", AId::Dy),
-        ("preserveAlpha", AId::PreserveAlpha),
-        ("preserveAspectRatio", AId::PreserveAspectRatio),
-        ("lighting-color", AId::LightingColor),
-        ("stroke-dashoffset", AId::StrokeDashoffset),
-        ("word-spacing", AId::WordSpacing),
-        ("font-style", AId::FontStyle),
-        ("edgeMode", AId::EdgeMode),
-        ("pointsAtY", AId::PointsAtY),
-        ("id", AId::Id),
-        ("type", AId::Type),
-        ("targetX", AId::TargetX),
-        ("fill", AId::Fill),
+        ("x2", AId::X2),
+        ("color-interpolation-filters", AId::ColorInterpolationFilters),
+        ("text-decoration", AId::TextDecoration),
         ("k3", AId::K3),
-        ("kernelUnitLength", AId::KernelUnitLength),
-        ("viewBox", AId::ViewBox),
-        ("baseFrequency", AId::BaseFrequency),
+        ("stroke-linecap", AId::StrokeLinecap),
+        ("numOctaves", AId::NumOctaves),
+        ("unicode", AId::Unicode),
+        ("stroke-linejoin", AId::StrokeLinejoin),
+        ("lengthAdjust", AId::LengthAdjust),
+        ("class", AId::Class),
+        ("cx", AId::Cx),
+        ("stdDeviation", AId::StdDeviation),
+        ("filter", AId::Filter),
+        ("g1", AId::G1),
+        ("orient", AId::Orient),
+        ("in", AId::In),
+        ("refX", AId::RefX),
+        ("ry", AId::Ry),
         ("stroke", AId::Stroke),
-        ("divisor", AId::Divisor),
-        ("slope", AId::Slope),
-        ("markerUnits", AId::MarkerUnits),
+        ("diffuseConstant", AId::DiffuseConstant),
+        ("fill", AId::Fill),
+        ("alignment-baseline", AId::AlignmentBaseline),
+        ("path", AId::Path),
+        ("k1", AId::K1),
         ("d", AId::D),
-        ("clipPathUnits", AId::ClipPathUnits),
-        ("stop-opacity", AId::StopOpacity),
-        ("transform", AId::Transform),
-        ("stroke-dasharray", AId::StrokeDasharray),
-        ("dx", AId::Dx),
-        ("specularExponent", AId::SpecularExponent),
-        ("z", AId::Z),
-        ("direction", AId::Direction),
-        ("letter-spacing", AId::LetterSpacing),
-        ("stroke-miterlimit", AId::StrokeMiterlimit),
+        ("inline-size", AId::InlineSize),
+        ("in2", AId::In2),
+        ("y", AId::Y),
+        ("vector-effect", AId::VectorEffect),
+        ("requiredExtensions", AId::RequiredExtensions),
+        ("preserveAspectRatio", AId::PreserveAspectRatio),
+        ("opacity", AId::Opacity),
+        ("text-rendering", AId::TextRendering),
+        ("word-spacing", AId::WordSpacing),
+        ("bias", AId::Bias),
         ("overflow", AId::Overflow),
-        ("order", AId::Order),
-        ("intercept", AId::Intercept),
+        ("baseline-shift", AId::BaselineShift),
+        ("baseFrequency", AId::BaseFrequency),
+        ("z", AId::Z),
+        ("y2", AId::Y2),
+        ("surfaceScale", AId::SurfaceScale),
+        ("markerWidth", AId::MarkerWidth),
+        ("points", AId::Points),
+        ("flood-opacity", AId::FloodOpacity),
+        ("gradientTransform", AId::GradientTransform),
+        ("azimuth", AId::Azimuth),
+        ("primitiveUnits", AId::PrimitiveUnits),
+        ("u1", AId::U1),
+        ("slope", AId::Slope),
+        ("isolation", AId::Isolation),
         ("operator", AId::Operator),
-        ("font-variant", AId::FontVariant),
-        ("filterUnits", AId::FilterUnits),
-        ("values", AId::Values),
-        ("filter", AId::Filter),
-        ("rotate", AId::Rotate),
-        ("href", AId::Href),
-        ("requiredExtensions", AId::RequiredExtensions),
+        ("marker-start", AId::MarkerStart),
+        ("fill-opacity", AId::FillOpacity),
+        ("font-size", AId::FontSize),
+        ("rx", AId::Rx),
+        ("fy", AId::Fy),
+        ("scale", AId::Scale),
+        ("startOffset", AId::StartOffset),
+        ("mix-blend-mode", AId::MixBlendMode),
+        ("result", AId::Result),
+        ("specularConstant", AId::SpecularConstant),
+        ("marker-end", AId::MarkerEnd),
         ("font-stretch", AId::FontStretch),
-        ("clip-path", AId::ClipPath),
         ("x1", AId::X1),
-        ("pointsAtX", AId::PointsAtX),
-        ("k4", AId::K4),
-        ("font-weight", AId::FontWeight),
-        ("class", AId::Class),
-        ("patternTransform", AId::PatternTransform),
-        ("markerHeight", AId::MarkerHeight),
-        ("writing-mode", AId::WritingMode),
-        ("clip-rule", AId::ClipRule),
-        ("y1", AId::Y1),
-        ("patternContentUnits", AId::PatternContentUnits),
-        ("primitiveUnits", AId::PrimitiveUnits),
-        ("x2", AId::X2),
-        ("y", AId::Y),
-        ("marker-mid", AId::MarkerMid),
-        ("offset", AId::Offset),
-        ("stroke-width", AId::StrokeWidth),
-        ("markerWidth", AId::MarkerWidth),
-        ("fill-opacity", AId::FillOpacity),
-        ("refX", AId::RefX),
-        ("maskContentUnits", AId::MaskContentUnits),
-        ("shape-rendering", AId::ShapeRendering),
         ("amplitude", AId::Amplitude),
-        ("stitchTiles", AId::StitchTiles),
-        ("maskUnits", AId::MaskUnits),
+        ("type", AId::Type),
+        ("radius", AId::Radius),
+        ("shape-rendering", AId::ShapeRendering),
+        ("mask-type", AId::MaskType),
+        ("horiz-adv-x", AId::HorizAdvX),
+        ("color-interpolation", AId::ColorInterpolation),
+        ("font-family", AId::FontFamily),
+        ("pointsAtZ", AId::PointsAtZ),
+        ("clip-rule", AId::ClipRule),
+        ("rotate", AId::Rotate),
+        ("lighting-color", AId::LightingColor),
+        ("stroke-dasharray", AId::StrokeDasharray),
+        ("underline-thickness", AId::UnderlineThickness),
+        ("spreadMethod", AId::SpreadMethod),
+        ("stroke-miterlimit", AId::StrokeMiterlimit),
+        ("dominant-baseline", AId::DominantBaseline),
+        ("clip-path", AId::ClipPath),
         ("fx", AId::Fx),
+        ("ascent", AId::Ascent),
         ("color", AId::Color),
-        ("numOctaves", AId::NumOctaves),
-        ("stdDeviation", AId::StdDeviation),
-        ("y2", AId::Y2),
-        ("mask", AId::Mask),
-        ("spreadMethod", AId::SpreadMethod),
+        ("font-feature-settings", AId::FontFeatureSettings),
+        ("space", AId::Space),
+        ("k", AId::K),
+        ("u2", AId::U2),
+        ("height", AId::Height),
         ("display", AId::Display),
-        ("opacity", AId::Opacity),
-        ("font-size", AId::FontSize),
-        ("stroke-linejoin", AId::StrokeLinejoin),
-        ("image-rendering", AId::ImageRendering),
-        ("azimuth", AId::Azimuth),
-        ("in", AId::In),
-        ("ry", AId::Ry),
-        ("bias", AId::Bias),
-        ("radius", AId::Radius),
-        ("enable-background", AId::EnableBackground),
-        ("gradientUnits", AId::GradientUnits),
-        ("specularConstant", AId::SpecularConstant),
-        ("limitingConeAngle", AId::LimitingConeAngle),
-        ("flood-color", AId::FloodColor),
-        ("scale", AId::Scale),
-        ("x", AId::X),
-        ("diffuseConstant", AId::DiffuseConstant),
+        ("style", AId::Style),
+        ("white-space", AId::WhiteSpace),
+        ("id", AId::Id),
+        ("kerning", AId::Kerning),
         ("refY", AId::RefY),
+        ("values", AId::Values),
+        ("descent", AId::Descent),
+        ("hatchUnits", AId::HatchUnits),
+        ("cy", AId::Cy),
+        ("enable-background", AId::EnableBackground),
+        ("font-style", AId::FontStyle),
         ("yChannelSelector", AId::YChannelSelector),
-        ("style", AId::Style),
-        ("gradientTransform", AId::GradientTransform),
-        ("fill-rule", AId::FillRule),
-        ("height", AId::Height),
-        ("kernelMatrix", AId::KernelMatrix),
-        ("text-anchor", AId::TextAnchor),
-        ("stroke-linecap", AId::StrokeLinecap),
-        ("text-decoration", AId::TextDecoration),
-        ("rx", AId::Rx),
-        ("requiredFeatures", AId::RequiredFeatures),
-        ("patternUnits", AId::PatternUnits),
-        ("mode", AId::Mode),
-        ("marker-start", AId::MarkerStart),
-        ("visibility", AId::Visibility),
-        ("result", AId::Result),
-        ("seed", AId::Seed),
-        ("in2", AId::In2),
-        ("cx", AId::Cx),
-        ("k2", AId::K2),
-        ("flood-opacity", AId::FloodOpacity),
-        ("k1", AId::K1),
-        ("text-rendering", AId::TextRendering),
+        ("markerHeight", AId::MarkerHeight),
+        ("units-per-em", AId::UnitsPerEm),
+        ("preserveAlpha", AId::PreserveAlpha),
+        ("writing-mode", AId::WritingMode),
+        ("edgeMode", AId::EdgeMode),
+        ("g2", AId::G2),
+        ("xChannelSelector", AId::XChannelSelector),
+        ("href", AId::Href),
+        ("stop-opacity", AId::StopOpacity),
+        ("mask-mode", AId::MaskMode),
+        ("transform-origin", AId::TransformOrigin),
+        ("pitch", AId::Pitch),
         ("r", AId::R),
-        ("marker-end", AId::MarkerEnd),
-        ("surfaceScale", AId::SurfaceScale),
+        ("font-variant", AId::FontVariant),
+        ("k4", AId::K4),
+        ("targetX", AId::TargetX),
+        ("image-rendering", AId::ImageRendering),
+        ("underline-position", AId::UnderlinePosition),
+        ("order", AId::Order),
         ("tableValues", AId::TableValues),
-        ("orient", AId::Orient),
-        ("color-interpolation-filters", AId::ColorInterpolationFilters),
-        ("points", AId::Points),
-        ("baseline-shift", AId::BaselineShift),
-        ("elevation", AId::Elevation),
+        ("clipPathUnits", AId::ClipPathUnits),
+        ("patternContentUnits", AId::PatternContentUnits),
+        ("maskUnits", AId::MaskUnits),
+        ("textLength", AId::TextLength),
+        ("font-weight", AId::FontWeight),
+        ("marker-mid", AId::MarkerMid),
+        ("patternTransform", AId::PatternTransform),
+        ("stroke-dashoffset", AId::StrokeDashoffset),
+        ("x-height", AId::XHeight),
+        ("systemLanguage", AId::SystemLanguage),
+        ("pointsAtY", AId::PointsAtY),
+        ("shape-inside", AId::ShapeInside),
+        ("flood-color", AId::FloodColor),
+        ("kernelUnitLength", AId::KernelUnitLength),
+        ("gradientUnits", AId::GradientUnits),
+        ("targetY", AId::TargetY),
         ("width", AId::Width),
-        ("font-family", AId::FontFamily),
-        ("space", AId::Space),
-        ("startOffset", AId::StartOffset),
-        ("fy", AId::Fy),
+        ("visibility", AId::Visibility),
+        ("viewBox", AId::ViewBox),
+        ("maskContentUnits", AId::MaskContentUnits),
+        ("glyph-name", AId::GlyphName),
+        ("specularExponent", AId::SpecularExponent),
+        ("filterUnits", AId::FilterUnits),
+        ("elevation", AId::Elevation),
+        ("text-anchor", AId::TextAnchor),
+        ("seed", AId::Seed),
+        ("requiredFeatures", AId::RequiredFeatures),
+        ("stroke-width", AId::StrokeWidth),
+        ("mask", AId::Mask),
+        ("dy", AId::Dy),
+        ("stitchTiles", AId::StitchTiles),
         ("stroke-opacity", AId::StrokeOpacity),
-        ("targetY", AId::TargetY),
+        ("y1", AId::Y1),
+        ("hatchContentUnits", AId::HatchContentUnits),
+        ("patternUnits", AId::PatternUnits),
+        ("direction", AId::Direction),
+        ("markerUnits", AId::MarkerUnits),
+        ("unicode-bidi", AId::UnicodeBidi),
+        ("offset", AId::Offset),
+        ("shape-padding", AId::ShapePadding),
+        ("k2", AId::K2),
+        ("divisor", AId::Divisor),
+        ("x", AId::X),
+        ("letter-spacing", AId::LetterSpacing),
         ("exponent", AId::Exponent),
+        ("stop-color", AId::StopColor),
+        ("dx", AId::Dx),
+        ("pointsAtX", AId::PointsAtX),
+        ("intercept", AId::Intercept),
+        ("fill-rule", AId::FillRule),
+        ("limitingConeAngle", AId::LimitingConeAngle),
+        ("mode", AId::Mode),
+        ("paint-order", AId::PaintOrder),
+        ("transform-box", AId::TransformBox),
+        ("transform", AId::Transform),
+        ("strikethrough-position", AId::StrikethroughPosition),
+        ("kernelMatrix", AId::KernelMatrix),
     ],
 };
 
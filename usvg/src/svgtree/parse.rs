@@ -2,6 +2,9 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::str::FromStr;
 use std::collections::HashMap;
@@ -10,9 +13,12 @@ use log::warn;
 
 pub use roxmltree::Error;
 
-use crate::tree;
-use crate::Rect;
-use super::{Document, Attribute, AId, EId, Node, NodeId, NodeKind, NodeData, AttributeValue};
+use crate::{tree, utils, Options, Rect};
+use super::css::{self, Origin, StyleSheet};
+use super::{
+    Document, Attribute, AId, EId, Node, NodeId, NodeKind, NodeData, AttributeValue, TransformOrigin,
+    FilterFunction, FilterValue,
+};
 
 const SVG_NS: &str = "http://www.w3.org/2000/svg";
 const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
@@ -20,8 +26,8 @@ const XML_NAMESPACE_NS: &str = "http://www.w3.org/XML/1998/namespace";
 
 
 impl Document {
-    pub fn parse(text: &str) -> Result<Document, Error> {
-        parse(text)
+    pub fn parse(text: &str, opt: &Options) -> Result<Document, Error> {
+        parse(text, opt)
     }
 
     fn append(&mut self, parent_id: NodeId, kind: NodeKind) -> NodeId {
@@ -69,7 +75,7 @@ impl Document {
     }
 }
 
-fn parse(text: &str) -> Result<Document, Error> {
+fn parse(text: &str, opt: &Options) -> Result<Document, Error> {
     let xml = roxmltree::Document::parse(text)?;
 
     let mut doc = Document {
@@ -87,9 +93,52 @@ fn parse(text: &str) -> Result<Document, Error> {
         kind: NodeKind::Root,
     });
 
-    let style_sheet = resolve_css(&xml);
+    let dir = opt.path.as_ref().and_then(|path| path.parent());
+    let css_sources = collect_css(&xml, dir, opt);
+    let style_sheet = resolve_css(&css_sources, opt);
 
-    parse_xml_node_children(xml.root(), xml.root(), doc.root().id, &style_sheet, false, &mut doc);
+    // Load all SVG files referenced by `use` elements.
+    // Each file is loaded only once, even when it's referenced multiple times.
+    //
+    // The main document is registered first, so files that reference it back
+    // will not load it again.
+    let path = opt.path.as_ref().and_then(|path| path.canonicalize().ok());
+    let mut external_texts = Vec::new();
+    if let Some(ref path) = path {
+        external_texts.push((path.clone(), String::new()));
+    }
+
+    if opt.load_external_use {
+        load_external_files(&xml, dir, &mut external_texts);
+    }
+
+    let external_texts = if path.is_some() { &external_texts[1..] } else { &external_texts[..] };
+    let external_xmls: Vec<_> = external_texts.iter().filter_map(|(path, text)| {
+        roxmltree::Document::parse(text).ok().map(|xml| (path, xml))
+    }).collect();
+
+    let external_css: Vec<_> = external_xmls.iter()
+        .map(|(path, xml)| collect_css(xml, path.parent(), opt))
+        .collect();
+
+    let external_docs: Vec<_> = external_xmls.iter().zip(&external_css)
+        .map(|((path, xml), css_sources)| ExternalDocument {
+            path,
+            xml,
+            style_sheet: resolve_css(css_sources, opt),
+        })
+        .collect();
+
+    let state = ParseState {
+        path: path.as_deref(),
+        xml: &xml,
+        dir,
+        style_sheet: &style_sheet,
+        external_docs: &external_docs,
+        use_stack: RefCell::new(Vec::new()),
+    };
+
+    parse_xml_node_children(xml.root(), xml.root(), doc.root().id, &state, false, &mut doc);
 
     // Check that the root element is `svg`.
     match doc.root().first_element_child() {
@@ -118,6 +167,48 @@ fn parse(text: &str) -> Result<Document, Error> {
     Ok(doc)
 }
 
+/// An SVG document referenced by a `use` element.
+struct ExternalDocument<'a> {
+    path: &'a Path,
+    xml: &'a roxmltree::Document<'a>,
+    style_sheet: StyleSheet<'a>,
+}
+
+struct ParseState<'a> {
+    /// A canonical path of the main document.
+    path: Option<&'a Path>,
+    xml: &'a roxmltree::Document<'a>,
+    /// A directory of the main document.
+    dir: Option<&'a Path>,
+    style_sheet: &'a StyleSheet<'a>,
+    external_docs: &'a [ExternalDocument<'a>],
+    /// External elements that are currently being referenced.
+    /// Used to detect recursive external `use` elements.
+    use_stack: RefCell<Vec<(usize, roxmltree::NodeId)>>,
+}
+
+impl<'a> ParseState<'a> {
+    fn external_doc(&self, node: roxmltree::Node) -> Option<&ExternalDocument<'a>> {
+        self.external_docs.iter().find(|d| std::ptr::eq(d.xml, node.document()))
+    }
+
+    /// Returns a style sheet of the document that contains the node.
+    fn style_sheet(&self, node: roxmltree::Node) -> &StyleSheet<'a> {
+        match self.external_doc(node) {
+            Some(d) => &d.style_sheet,
+            None => self.style_sheet,
+        }
+    }
+
+    /// Returns a directory of the document that contains the node.
+    fn dir(&self, node: roxmltree::Node) -> Option<&'a Path> {
+        match self.external_doc(node) {
+            Some(d) => d.path.parent(),
+            None => self.dir,
+        }
+    }
+}
+
 fn parse_tag_name(node: roxmltree::Node) -> Option<EId> {
     if !node.is_element() {
         return None;
@@ -134,12 +225,12 @@ fn parse_xml_node_children(
     parent: roxmltree::Node,
     origin: roxmltree::Node,
     parent_id: NodeId,
-    style_sheet: &simplecss::StyleSheet,
+    state: &ParseState,
     ignore_ids: bool,
     doc: &mut Document,
 ) {
     for node in parent.children() {
-        parse_xml_node(node, origin, parent_id, style_sheet, ignore_ids, doc);
+        parse_xml_node(node, origin, parent_id, state, ignore_ids, doc);
     }
 }
 
@@ -147,7 +238,7 @@ fn parse_xml_node(
     node: roxmltree::Node,
     origin: roxmltree::Node,
     parent_id: NodeId,
-    style_sheet: &simplecss::StyleSheet,
+    state: &ParseState,
     ignore_ids: bool,
     doc: &mut Document,
 ) {
@@ -165,13 +256,13 @@ fn parse_xml_node(
         tag_name = EId::G;
     }
 
-    let node_id = parse_svg_element(node, parent_id, tag_name, style_sheet, ignore_ids, doc);
+    let node_id = parse_svg_element(node, parent_id, tag_name, state, ignore_ids, doc);
     if tag_name == EId::Text {
-        parse_svg_text_element(node, node_id, style_sheet, doc);
+        parse_svg_text_element(node, node_id, state, doc);
     } else if tag_name == EId::Use {
-        parse_svg_use_element(node, origin, node_id, style_sheet, doc);
+        parse_svg_use_element(node, origin, node_id, state, doc);
     } else {
-        parse_xml_node_children(node, origin, node_id, style_sheet, ignore_ids, doc);
+        parse_xml_node_children(node, origin, node_id, state, ignore_ids, doc);
     }
 }
 
@@ -179,7 +270,7 @@ fn parse_svg_element(
     xml_node: roxmltree::Node,
     parent_id: NodeId,
     tag_name: EId,
-    style_sheet: &simplecss::StyleSheet,
+    state: &ParseState,
     ignore_ids: bool,
     doc: &mut Document,
 ) -> NodeId {
@@ -226,38 +317,27 @@ fn parse_svg_element(
     };
 
     // Apply CSS.
-    for rule in &style_sheet.rules {
-        if rule.selector.matches(&XmlNode(xml_node)) {
-            for declaration in &rule.declarations {
-                // TODO: preform XML attribute normalization
-                if let Some(aid) = AId::from_str(declaration.name) {
-                    // Parse only the presentation attributes.
-                    // `transform` isn't a presentation attribute, but should be parsed anyway.
-                    if aid.is_presentation() || aid == AId::Transform {
-                        insert_attribute(aid, declaration.value);
-                    }
-                } else if declaration.name == "marker" {
-                    insert_attribute(AId::MarkerStart, declaration.value);
-                    insert_attribute(AId::MarkerMid, declaration.value);
-                    insert_attribute(AId::MarkerEnd, declaration.value);
-                }
+    let declarations = state.style_sheet(xml_node).collect_declarations(
+        &XmlNode(xml_node),
+        xml_node.attribute("style"),
+    );
+
+    for declaration in declarations {
+        // TODO: preform XML attribute normalization
+        if let Some(aid) = AId::from_str(declaration.name) {
+            // Parse only the presentation attributes.
+            // `transform` isn't a presentation attribute, but should be parsed anyway.
+            if aid.is_presentation() || aid == AId::Transform {
+                insert_attribute(aid, declaration.value);
             }
+        } else if declaration.name == "marker" {
+            insert_attribute(AId::MarkerStart, declaration.value);
+            insert_attribute(AId::MarkerMid, declaration.value);
+            insert_attribute(AId::MarkerEnd, declaration.value);
         }
     }
 
-    // Split a `style` attribute.
-    if let Some(value) = xml_node.attribute("style") {
-        for declaration in simplecss::DeclarationTokenizer::from(value) {
-            // TODO: preform XML attribute normalization
-            if let Some(aid) = AId::from_str(declaration.name) {
-                // Parse only the presentation attributes.
-                // `transform` isn't a presentation attribute, but should be parsed anyway.
-                if aid.is_presentation() || aid == AId::Transform {
-                    insert_attribute(aid, declaration.value);
-                }
-            }
-        }
-    }
+    resolve_transform_origin(attrs_start_idx, doc);
 
     let node_id = doc.append(parent_id, NodeKind::Element {
         tag_name,
@@ -338,7 +418,11 @@ fn parse_svg_attribute(
         | AId::RefX | AId::RefY
         | AId::Width | AId::Height
         | AId::MarkerWidth | AId::MarkerHeight
-        | AId::StartOffset => {
+        | AId::StartOffset
+        | AId::Pitch
+        | AId::InlineSize
+        | AId::ShapePadding
+        | AId::TextLength => {
             AttributeValue::Length(svgtypes::Length::from_str(value)?)
         }
 
@@ -372,13 +456,17 @@ fn parse_svg_attribute(
         }
 
           AId::Amplitude
+        | AId::Ascent
         | AId::Azimuth
         | AId::Bias
+        | AId::Descent
         | AId::DiffuseConstant
         | AId::Divisor
         | AId::Elevation
         | AId::Exponent
+        | AId::HorizAdvX
         | AId::Intercept
+        | AId::K
         | AId::K1
         | AId::K2
         | AId::K3
@@ -393,10 +481,15 @@ fn parse_svg_attribute(
         | AId::Slope
         | AId::SpecularConstant
         | AId::SpecularExponent
+        | AId::StrikethroughPosition
         | AId::StrokeMiterlimit
         | AId::SurfaceScale
         | AId::TargetX
         | AId::TargetY
+        | AId::UnderlinePosition
+        | AId::UnderlineThickness
+        | AId::UnitsPerEm
+        | AId::XHeight
         | AId::Z => {
             AttributeValue::Number(parse_number(value)?)
         }
@@ -408,6 +501,9 @@ fn parse_svg_attribute(
             }
         }
 
+        AId::Fill | AId::Stroke if value.trim() == "context-fill" => AttributeValue::ContextFill,
+        AId::Fill | AId::Stroke if value.trim() == "context-stroke" => AttributeValue::ContextStroke,
+
         AId::Fill => {
             match svgtypes::Paint::from_str(value) {
                 Ok(svgtypes::Paint::None) => AttributeValue::None,
@@ -436,12 +532,26 @@ fn parse_svg_attribute(
             }
         }
 
+        AId::Filter => {
+            if value.trim() == "none" {
+                AttributeValue::None
+            } else {
+                let list = parse_filter_list(value)?;
+                match list.as_slice() {
+                    // A single link is the most common case,
+                    // so we store it just like any other link.
+                    [FilterValue::Url(ref link)] => AttributeValue::Link(link.clone()),
+                    _ => AttributeValue::Filters(list),
+                }
+            }
+        }
+
           AId::ClipPath
-        | AId::Filter
         | AId::MarkerEnd
         | AId::MarkerMid
         | AId::MarkerStart
-        | AId::Mask => {
+        | AId::Mask
+        | AId::ShapeInside => {
             match value {
                 "none" => AttributeValue::None,
                 _ => {
@@ -466,7 +576,12 @@ fn parse_svg_attribute(
         }
 
         AId::D => {
-            let segments = parse_path(value);
+            let segments = if tag_name == EId::Hatchpath && !value.trim_start().starts_with(&['M', 'm'][..]) {
+                // A hatch path can start with any command. `M 0 0` is implied in this case.
+                parse_path(&format!("M 0 0 {}", value))
+            } else {
+                parse_path(value)
+            };
             if segments.len() >= 2 {
                 AttributeValue::Path(Rc::new(segments))
             } else {
@@ -494,6 +609,13 @@ fn parse_svg_attribute(
                 _ => AttributeValue::String(value.to_string()),
             }
         }
+        AId::Kerning => {
+            match value {
+                "auto" => AttributeValue::String(value.to_string()),
+                _ => AttributeValue::Length(svgtypes::Length::from_str(value)?),
+            }
+        }
+
           AId::LetterSpacing
         | AId::WordSpacing => {
             match value {
@@ -511,7 +633,7 @@ fn parse_svg_attribute(
 
         AId::Orient => {
             match value {
-                "auto" => AttributeValue::String(value.to_string()),
+                "auto" | "auto-start-reverse" => AttributeValue::String(value.to_string()),
                 _ => AttributeValue::Angle(svgtypes::Angle::from_str(value)?),
             }
         }
@@ -524,6 +646,10 @@ fn parse_svg_attribute(
             AttributeValue::AspectRatio(svgtypes::AspectRatio::from_str(value)?)
         }
 
+        AId::Rotate if tag_name == EId::Hatch => {
+            AttributeValue::Number(parse_number(value)?)
+        }
+
           AId::BaseFrequency
         | AId::KernelMatrix
         | AId::Radius
@@ -537,6 +663,14 @@ fn parse_svg_attribute(
             AttributeValue::EnableBackground(parse_enable_background(value)?)
         }
 
+        AId::PaintOrder => {
+            AttributeValue::PaintOrder(parse_paint_order(value)?)
+        }
+
+        AId::TransformOrigin => {
+            AttributeValue::TransformOrigin(parse_transform_origin(value)?)
+        }
+
         _ => {
             AttributeValue::String(value.to_string())
         }
@@ -789,6 +923,333 @@ fn parse_enable_background(value: &str) -> Result<tree::EnableBackground, svgtyp
     }
 }
 
+fn parse_paint_order(value: &str) -> Result<tree::PaintOrder, svgtypes::Error> {
+    use tree::PaintOrderKind;
+
+    let value = value.trim();
+    if value == "normal" {
+        return Ok(tree::PaintOrder::default());
+    }
+
+    let mut order = Vec::with_capacity(3);
+    for name in value.split_whitespace() {
+        let kind = match name {
+            "fill" => PaintOrderKind::Fill,
+            "stroke" => PaintOrderKind::Stroke,
+            "markers" => PaintOrderKind::Markers,
+            _ => return Err(svgtypes::Error::InvalidValue),
+        };
+
+        // Each item can be specified only once.
+        if order.contains(&kind) {
+            return Err(svgtypes::Error::InvalidValue);
+        }
+
+        order.push(kind);
+    }
+
+    if order.is_empty() {
+        return Err(svgtypes::Error::InvalidValue);
+    }
+
+    // Omitted items are painted after the specified ones, in the default order.
+    for kind in &tree::PaintOrder::default().order {
+        if !order.contains(kind) {
+            order.push(*kind);
+        }
+    }
+
+    Ok(tree::PaintOrder { order: [order[0], order[1], order[2]] })
+}
+
+fn parse_transform_origin(value: &str) -> Result<TransformOrigin, svgtypes::Error> {
+    #[derive(Clone, Copy)]
+    enum Item {
+        /// `left` or `right`.
+        Horizontal(svgtypes::Length),
+        /// `top` or `bottom`.
+        Vertical(svgtypes::Length),
+        /// `center`.
+        Center,
+        Length(svgtypes::Length),
+    }
+
+    impl Item {
+        fn value(self) -> svgtypes::Length {
+            match self {
+                Item::Horizontal(n) | Item::Vertical(n) | Item::Length(n) => n,
+                Item::Center => percent(50.0),
+            }
+        }
+
+        fn is_x(self) -> bool {
+            !matches!(self, Item::Vertical(_))
+        }
+
+        fn is_y(self) -> bool {
+            !matches!(self, Item::Horizontal(_))
+        }
+
+        fn is_keyword(self) -> bool {
+            !matches!(self, Item::Length(_))
+        }
+    }
+
+    fn percent(n: f64) -> svgtypes::Length {
+        svgtypes::Length::new(n, svgtypes::LengthUnit::Percent)
+    }
+
+    fn parse_item(text: &str) -> Result<Item, svgtypes::Error> {
+        Ok(match text {
+            "left" => Item::Horizontal(percent(0.0)),
+            "right" => Item::Horizontal(percent(100.0)),
+            "top" => Item::Vertical(percent(0.0)),
+            "bottom" => Item::Vertical(percent(100.0)),
+            "center" => Item::Center,
+            _ => Item::Length(svgtypes::Length::from_str(text)?),
+        })
+    }
+
+    let items: Vec<&str> = value.split_whitespace().collect();
+    let (x, y) = match items.as_slice() {
+        [item] => {
+            let item = parse_item(item)?;
+            if item.is_x() {
+                (item.value(), percent(50.0))
+            } else {
+                (percent(50.0), item.value())
+            }
+        }
+        [first, second] | [first, second, _] => {
+            // The third item is a `z` offset, which is not supported.
+            if let Some(z) = items.get(2) {
+                svgtypes::Length::from_str(z)?;
+            }
+
+            let first = parse_item(first)?;
+            let second = parse_item(second)?;
+            if first.is_x() && second.is_y() {
+                (first.value(), second.value())
+            } else if first.is_keyword() && second.is_keyword() && first.is_y() && second.is_x() {
+                // Keywords can be specified in any order, like `top left`.
+                (second.value(), first.value())
+            } else {
+                return Err(svgtypes::Error::InvalidValue);
+            }
+        }
+        _ => return Err(svgtypes::Error::InvalidValue),
+    };
+
+    Ok(TransformOrigin { x, y })
+}
+
+fn parse_filter_list(value: &str) -> Result<Vec<FilterValue>, svgtypes::Error> {
+    let mut s = svgtypes::Stream::from(value);
+    let mut list = Vec::new();
+
+    s.skip_spaces();
+    while !s.at_end() {
+        let func_start = s.pos();
+        let name = s.consume_ident();
+        s.consume_byte(b'(')?;
+
+        // Arguments can contain nested functions, like `rgb()` in `drop-shadow()`.
+        let start = s.pos();
+        let mut depth = 0;
+        loop {
+            match s.curr_byte()? {
+                b'(' => depth += 1,
+                b')' if depth == 0 => break,
+                b')' => depth -= 1,
+                _ => {}
+            }
+
+            s.advance(1);
+        }
+        let args = s.slice_back(start).trim();
+        s.advance(1);
+
+        if name == "url" {
+            let mut func_s = svgtypes::Stream::from(s.slice_back(func_start));
+            let link = func_s.parse_func_iri()?;
+            list.push(FilterValue::Url(link.to_string()));
+        } else {
+            list.push(FilterValue::Function(parse_filter_function(name, args)?));
+        }
+
+        s.skip_spaces();
+    }
+
+    if list.is_empty() {
+        return Err(svgtypes::Error::InvalidValue);
+    }
+
+    Ok(list)
+}
+
+fn parse_filter_function(name: &str, args: &str) -> Result<FilterFunction, svgtypes::Error> {
+    // A non-negative `<number> | <percentage>` with a default value of 1.
+    fn parse_amount(args: &str, max: f64) -> Result<f64, svgtypes::Error> {
+        if args.is_empty() {
+            return Ok(1.0);
+        }
+
+        let n = match svgtypes::Length::from_str(args)? {
+            svgtypes::Length { num, unit: svgtypes::LengthUnit::None } => num,
+            svgtypes::Length { num, unit: svgtypes::LengthUnit::Percent } => num / 100.0,
+            _ => return Err(svgtypes::Error::InvalidValue),
+        };
+
+        if n.is_sign_negative() {
+            return Err(svgtypes::Error::InvalidValue);
+        }
+
+        Ok(n.min(max))
+    }
+
+    Ok(match name {
+        "blur" => {
+            let std_dev = if args.is_empty() {
+                svgtypes::Length::zero()
+            } else {
+                parse_filter_length(args)?
+            };
+
+            if std_dev.num.is_sign_negative() {
+                return Err(svgtypes::Error::InvalidValue);
+            }
+
+            FilterFunction::Blur(std_dev)
+        }
+        "brightness" => FilterFunction::Brightness(parse_amount(args, f64::MAX)?),
+        "contrast" => FilterFunction::Contrast(parse_amount(args, f64::MAX)?),
+        "drop-shadow" => parse_drop_shadow(args)?,
+        "grayscale" => FilterFunction::Grayscale(parse_amount(args, 1.0)?),
+        "hue-rotate" => {
+            let angle = if args.is_empty() {
+                0.0
+            } else if let Some(turns) = args.strip_suffix("turn") {
+                f64::from_str(turns).map_err(|_| svgtypes::Error::InvalidValue)? * 360.0
+            } else {
+                let mut s = svgtypes::Stream::from(args);
+                let angle = s.parse_angle()?;
+                if !s.at_end() {
+                    return Err(svgtypes::Error::InvalidValue);
+                }
+
+                match angle.unit {
+                    svgtypes::AngleUnit::Degrees  => angle.num,
+                    svgtypes::AngleUnit::Gradians => angle.num * 180.0 / 200.0,
+                    svgtypes::AngleUnit::Radians  => angle.num.to_degrees(),
+                }
+            };
+
+            FilterFunction::HueRotate(angle)
+        }
+        "invert" => FilterFunction::Invert(parse_amount(args, 1.0)?),
+        "opacity" => FilterFunction::Opacity(parse_amount(args, 1.0)?),
+        "saturate" => FilterFunction::Saturate(parse_amount(args, f64::MAX)?),
+        "sepia" => FilterFunction::Sepia(parse_amount(args, 1.0)?),
+        _ => return Err(svgtypes::Error::InvalidValue),
+    })
+}
+
+fn parse_drop_shadow(args: &str) -> Result<FilterFunction, svgtypes::Error> {
+    // Split arguments by spaces, but not inside a color function.
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c.is_ascii_whitespace() && depth == 0 => {
+                items.push(&args[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&args[start..]);
+    items.retain(|item| !item.is_empty());
+
+    // A color can be set either before or after lengths.
+    let mut color = None;
+    if let Some(idx) = items.iter().position(|item| svgtypes::Length::from_str(item).is_err()) {
+        if idx != 0 && idx != items.len() - 1 {
+            return Err(svgtypes::Error::InvalidValue);
+        }
+
+        let item = items.remove(idx);
+        if item != "currentColor" {
+            color = Some(svgtypes::Color::from_str(item)?);
+        }
+    }
+
+    let mut lengths = Vec::new();
+    for item in items {
+        lengths.push(parse_filter_length(item)?);
+    }
+
+    let (dx, dy, std_dev) = match *lengths.as_slice() {
+        [dx, dy] => (dx, dy, svgtypes::Length::zero()),
+        [dx, dy, std_dev] if !std_dev.num.is_sign_negative() => (dx, dy, std_dev),
+        _ => return Err(svgtypes::Error::InvalidValue),
+    };
+
+    Ok(FilterFunction::DropShadow {
+        color,
+        dx,
+        dy,
+        std_dev,
+    })
+}
+
+fn parse_filter_length(text: &str) -> Result<svgtypes::Length, svgtypes::Error> {
+    let length = svgtypes::Length::from_str(text)?;
+    if length.unit == svgtypes::LengthUnit::Percent {
+        return Err(svgtypes::Error::InvalidValue);
+    }
+
+    Ok(length)
+}
+
+/// Applies `transform-origin` to the element `transform`.
+///
+/// Only user unit origins relative to the `view-box` can be resolved here.
+/// Everything else requires the element geometry or the viewport size
+/// and will be resolved during the conversion.
+fn resolve_transform_origin(attrs_start_idx: usize, doc: &mut Document) {
+    fn is_user_unit(length: svgtypes::Length) -> bool {
+        matches!(length.unit, svgtypes::LengthUnit::None | svgtypes::LengthUnit::Px)
+    }
+
+    let attrs = &mut doc.attrs[attrs_start_idx..];
+
+    let origin_idx = try_opt!(attrs.iter().position(|a| a.name == AId::TransformOrigin));
+    let origin = match attrs[origin_idx].value {
+        AttributeValue::TransformOrigin(origin) => origin,
+        _ => return,
+    };
+
+    let is_view_box = match attrs.iter().find(|a| a.name == AId::TransformBox) {
+        Some(attr) => matches!(attr.value, AttributeValue::String(ref s) if s == "view-box"),
+        None => true,
+    };
+
+    if !(is_view_box && is_user_unit(origin.x) && is_user_unit(origin.y)) {
+        return;
+    }
+
+    if let Some(attr) = attrs.iter_mut().find(|a| a.name == AId::Transform) {
+        if let AttributeValue::Transform(ref mut ts) = attr.value {
+            *ts = utils::transform_around(*ts, origin.x.num, origin.y.num);
+        }
+    }
+
+    doc.attrs.remove(attrs_start_idx + origin_idx);
+}
+
 fn resolve_inherit(
     parent_id: NodeId,
     tag_name: EId,
@@ -823,6 +1284,7 @@ fn resolve_inherit(
     // Fallback to a default value if possible.
     let value = match aid {
           AId::ImageRendering
+        | AId::Isolation
         | AId::ShapeRendering
         | AId::TextRendering => "auto",
 
@@ -832,15 +1294,20 @@ fn resolve_inherit(
         | AId::MarkerMid
         | AId::MarkerStart
         | AId::Mask
+        | AId::ShapeInside
         | AId::Stroke
         | AId::StrokeDasharray
-        | AId::TextDecoration => "none",
+        | AId::TextDecoration
+        | AId::VectorEffect => "none",
 
           AId::FontStretch
         | AId::FontStyle
         | AId::FontVariant
         | AId::FontWeight
         | AId::LetterSpacing
+        | AId::MixBlendMode
+        | AId::PaintOrder
+        | AId::WhiteSpace
         | AId::WordSpacing => "normal",
 
           AId::Fill
@@ -857,10 +1324,17 @@ fn resolve_inherit(
         | AId::FillRule => "nonzero",
 
         AId::BaselineShift =>               "baseline",
+        AId::ColorInterpolation =>          "sRGB",
         AId::ColorInterpolationFilters =>   "linearRGB",
+        AId::TransformBox =>                "view-box",
+        AId::TransformOrigin =>             "0 0",
         AId::Direction =>                   "ltr",
         AId::Display =>                     "inline",
         AId::FontSize =>                    "medium",
+        AId::InlineSize =>                  "0",
+        AId::MaskMode =>                    "match-source",
+        AId::MaskType =>                    "luminance",
+        AId::ShapePadding =>                "0",
         AId::StrokeDashoffset =>            "0",
         AId::StrokeLinecap =>               "butt",
         AId::StrokeLinejoin =>              "miter",
@@ -888,10 +1362,13 @@ fn parse_svg_use_element(
     node: roxmltree::Node,
     origin: roxmltree::Node,
     parent_id: NodeId,
-    style_sheet: &simplecss::StyleSheet,
+    state: &ParseState,
     doc: &mut Document,
 ) -> Option<()> {
-    let link = resolve_href(node)?;
+    let link = match resolve_href(node) {
+        Some(link) => link,
+        None => return parse_svg_external_use_element(node, parent_id, state, doc),
+    };
 
     if link == node || link == origin {
         warn!("Recursive 'use' detected. '{}' will be skipped.",
@@ -941,29 +1418,147 @@ fn parse_svg_use_element(
         return None;
     }
 
-    parse_xml_node(link, node, parent_id, style_sheet, true, doc);
+    parse_xml_node(link, node, parent_id, state, true, doc);
     Some(())
 }
 
+fn parse_svg_external_use_element(
+    node: roxmltree::Node,
+    parent_id: NodeId,
+    state: &ParseState,
+    doc: &mut Document,
+) -> Option<()> {
+    let href = node.attribute((XLINK_NS, "href"))?;
+    let (path, link_id) = split_external_href(href)?;
+    let path = resolve_external_path(path, state.dir(node)?)?;
+
+    // An external document can reference the main one.
+    let xml = if state.path == Some(path.as_path()) {
+        state.xml
+    } else {
+        state.external_docs.iter().find(|d| d.path == path)?.xml
+    };
+
+    let link = match xml.descendants().find(|n| n.attribute("id") == Some(link_id)) {
+        Some(link) => link,
+        None => {
+            warn!("'{}' doesn't have an element with '{}' id. Skipped.", path.display(), link_id);
+            return None;
+        }
+    };
+
+    let tag_name = parse_tag_name(link)?;
+    if tag_name == EId::Svg {
+        warn!("'use' elements linked to an 'svg' element are not supported. Skipped.");
+        return None;
+    }
+
+    let key = (xml as *const _ as usize, link.id());
+    if state.use_stack.borrow().contains(&key) {
+        warn!("Recursive 'use' detected. '{}' will be skipped.", href);
+        return None;
+    }
+
+    state.use_stack.borrow_mut().push(key);
+    parse_xml_node(link, node, parent_id, state, true, doc);
+    state.use_stack.borrow_mut().pop();
+
+    Some(())
+}
+
+/// Splits an external `use` link like `file.svg#id` into a path and an element id.
+fn split_external_href(href: &str) -> Option<(&str, &str)> {
+    // Local links are handled by `resolve_href`.
+    if href.starts_with('#') || href.starts_with("data:") {
+        return None;
+    }
+
+    let idx = href.find('#')?;
+    let (path, link_id) = (&href[..idx], &href[idx + 1..]);
+    if path.is_empty() || link_id.is_empty() {
+        return None;
+    }
+
+    Some((path, link_id))
+}
+
+fn resolve_external_path(path: &str, dir: &Path) -> Option<PathBuf> {
+    dir.join(path).canonicalize().ok()
+}
+
+/// Loads all SVG files referenced by `use` elements, recursively.
+fn load_external_files(
+    xml: &roxmltree::Document,
+    dir: Option<&Path>,
+    files: &mut Vec<(PathBuf, String)>,
+) {
+    for node in xml.descendants().filter(|n| n.has_tag_name((SVG_NS, "use"))) {
+        let href = try_opt_continue!(node.attribute((XLINK_NS, "href")));
+        let (path, _) = try_opt_continue!(split_external_href(href));
+
+        // Relative paths cannot be resolved without the document path.
+        let dir = match dir {
+            Some(dir) => dir,
+            None => {
+                warn!("'{}' cannot be loaded without `Options::path`. Skipped.", path);
+                continue;
+            }
+        };
+
+        let path = match resolve_external_path(path, dir) {
+            Some(path) => path,
+            None => {
+                warn!("Failed to load '{}'. Skipped.", path);
+                continue;
+            }
+        };
+
+        if files.iter().any(|(p, _)| *p == path) {
+            continue;
+        }
+
+        let text = match tree::load_svg_file(&path) {
+            Ok(text) => text,
+            Err(_) => {
+                warn!("Failed to load '{}'. Skipped.", path.display());
+                continue;
+            }
+        };
+
+        // Register the file before processing nested references,
+        // so recursive references will not be loaded again.
+        let idx = files.len();
+        files.push((path.clone(), String::new()));
+
+        match roxmltree::Document::parse(&text) {
+            Ok(nested_xml) => load_external_files(&nested_xml, path.parent(), files),
+            Err(_) => warn!("Failed to parse '{}'.", path.display()),
+        }
+
+        files[idx].1 = text;
+    }
+}
+
 fn parse_svg_text_element(
     parent: roxmltree::Node,
     parent_id: NodeId,
-    style_sheet: &simplecss::StyleSheet,
+    state: &ParseState,
     doc: &mut Document,
 ) {
     debug_assert_eq!(parent.tag_name().name(), "text");
 
-    let space = if doc.get(parent_id).has_attribute(AId::Space) {
+    let has_space = |n: &Node| n.has_attribute(AId::Space) || n.has_attribute(AId::WhiteSpace);
+    let space = if has_space(&doc.get(parent_id)) {
         get_xmlspace(doc, parent_id, XmlSpace::Default)
     } else {
-        if let Some(node) = doc.get(parent_id).ancestors().find(|n| n.has_attribute(AId::Space)) {
+        if let Some(node) = doc.get(parent_id).ancestors().find(has_space) {
             get_xmlspace(doc, node.id, XmlSpace::Default)
         } else {
             XmlSpace::Default
         }
     };
 
-    parse_svg_text_element_impl(parent, parent_id, style_sheet, space, doc);
+    parse_svg_text_element_impl(parent, parent_id, state, space, doc);
 
     trim_text_nodes(parent_id, space, doc);
 }
@@ -971,13 +1566,14 @@ fn parse_svg_text_element(
 fn parse_svg_text_element_impl(
     parent: roxmltree::Node,
     parent_id: NodeId,
-    style_sheet: &simplecss::StyleSheet,
+    state: &ParseState,
     space: XmlSpace,
     doc: &mut Document,
 ) {
     for node in parent.children() {
         if node.is_text() {
-            let text = trim_text(node.text().unwrap(), space);
+            let keep_line_feeds = preserves_line_feeds(doc, parent_id);
+            let text = trim_text(node.text().unwrap(), space, keep_line_feeds);
             doc.append(parent_id, NodeKind::Text(text));
             continue;
         }
@@ -1005,18 +1601,19 @@ fn parse_svg_text_element_impl(
             is_tref = true;
         }
 
-        let node_id = parse_svg_element(node, parent_id, tag_name, style_sheet, false, doc);
+        let node_id = parse_svg_element(node, parent_id, tag_name, state, false, doc);
         let space = get_xmlspace(doc, node_id, space);
 
         if is_tref {
             if let Some(href) = node.attribute((XLINK_NS, "href")) {
                 if let Some(text) = resolve_tref_text(node.document(), href) {
-                    let text = trim_text(&text, space);
+                    let keep_line_feeds = preserves_line_feeds(doc, node_id);
+                    let text = trim_text(&text, space, keep_line_feeds);
                     doc.append(node_id, NodeKind::Text(text));
                 }
             }
         } else {
-            parse_svg_text_element_impl(node, node_id, style_sheet, space, doc);
+            parse_svg_text_element_impl(node, node_id, state, space, doc);
         }
     }
 }
@@ -1051,6 +1648,13 @@ enum XmlSpace {
 }
 
 fn get_xmlspace(doc: &Document, node_id: NodeId, default: XmlSpace) -> XmlSpace {
+    // `white-space` takes precedence over `xml:space`.
+    match doc.get(node_id).attribute(AId::WhiteSpace) {
+        Some("pre") | Some("pre-wrap") | Some("break-spaces") => return XmlSpace::Preserve,
+        Some("normal") | Some("nowrap") | Some("pre-line") => return XmlSpace::Default,
+        _ => {}
+    }
+
     match doc.get(node_id).attribute(AId::Space) {
         Some("preserve") => XmlSpace::Preserve,
         Some(_) => XmlSpace::Default,
@@ -1058,6 +1662,14 @@ fn get_xmlspace(doc: &Document, node_id: NodeId, default: XmlSpace) -> XmlSpace
     }
 }
 
+/// Checks that line feeds should be preserved according to the `white-space` property.
+///
+/// Preserved line feeds are forced line breaks.
+fn preserves_line_feeds(doc: &Document, node_id: NodeId) -> bool {
+    matches!(doc.get(node_id).find_attribute(AId::WhiteSpace),
+        Some("pre") | Some("pre-wrap") | Some("pre-line") | Some("break-spaces"))
+}
+
 trait StrTrim {
     fn remove_first_space(&mut self);
     fn remove_last_space(&mut self);
@@ -1252,20 +1864,28 @@ fn collect_text_nodes(parent: Node, depth: usize, nodes: &mut Vec<(NodeId, usize
     }
 }
 
-fn trim_text(text: &str, space: XmlSpace) -> String {
+fn trim_text(text: &str, space: XmlSpace, keep_line_feeds: bool) -> String {
     let mut s = String::with_capacity(text.len());
 
     let mut prev = '0';
     for c in text.chars() {
         // \r, \n and \t should be converted into spaces.
         let c = match c {
+            '\n' if keep_line_feeds => '\n',
             '\r' | '\n' | '\t' => ' ',
             _ => c,
         };
 
-        // Skip continuous spaces.
-        if space == XmlSpace::Default && c == ' ' && c == prev {
-            continue;
+        if space == XmlSpace::Default {
+            // Skip continuous spaces and spaces after a line feed.
+            if c == ' ' && (prev == ' ' || prev == '\n') {
+                continue;
+            }
+
+            // Remove a space before a line feed.
+            if c == '\n' && prev == ' ' {
+                s.pop();
+            }
         }
 
         prev = c;
@@ -1276,17 +1896,104 @@ fn trim_text(text: &str, space: XmlSpace) -> String {
     s
 }
 
-fn resolve_css<'a>(xml: &'a roxmltree::Document<'a>) -> simplecss::StyleSheet<'a> {
-    let mut sheet = simplecss::StyleSheet::new();
+/// Collects the text of all style sheets in the cascade order.
+///
+/// Includes `style` elements, `xml-stylesheet` processing instructions
+/// and the style sheets they import.
+///
+/// `dir` is a directory of the document and is used to resolve relative paths.
+fn collect_css<'a>(
+    xml: &'a roxmltree::Document<'a>,
+    dir: Option<&Path>,
+    opt: &Options,
+) -> Vec<Cow<'a, str>> {
+    let mut stack = Vec::new();
+    let mut sources = Vec::new();
+
+    for node in xml.descendants() {
+        if let Some(pi) = node.pi() {
+            if pi.target == "xml-stylesheet" && opt.load_external_stylesheets {
+                let href = parse_xml_stylesheet(pi.value.unwrap_or(""), opt);
+                if let Some(path) = href.and_then(|href| css::resolve_path(href, dir)) {
+                    css::load_file(&path, opt, &mut stack, &mut sources);
+                }
+            }
+
+            continue;
+        }
+
+        if !node.has_tag_name("style") {
+            continue;
+        }
 
-    for node in xml.descendants().filter(|n| n.has_tag_name("style")) {
         match node.attribute("type") {
             Some("text/css") => {}
             Some(_) => continue,
             None => {}
         }
 
-        sheet.parse_more(try_opt_continue!(node.text()));
+        let text = try_opt_continue!(node.text());
+        if opt.load_external_stylesheets {
+            css::collect_imports(text, dir, opt, &mut stack, &mut sources);
+        }
+
+        sources.push(Cow::Borrowed(text));
+    }
+
+    sources
+}
+
+/// Parses `xml-stylesheet` processing instruction pseudo-attributes.
+///
+/// Returns a style sheet path when it should be applied.
+fn parse_xml_stylesheet<'a>(value: &'a str, opt: &Options) -> Option<&'a str> {
+    let mut href = None;
+    let mut kind = None;
+    let mut media = None;
+    let mut alternate = None;
+
+    let mut s = value.trim();
+    while !s.is_empty() {
+        let eq = s.find('=')?;
+        let name = s[..eq].trim();
+        let rest = s[eq + 1..].trim_start();
+        let quote = rest.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+
+        let end = rest[1..].find(quote)? + 1;
+        let value = &rest[1..end];
+        match name {
+            "href" => href = Some(value),
+            "type" => kind = Some(value),
+            "media" => media = Some(value),
+            "alternate" => alternate = Some(value),
+            _ => {}
+        }
+
+        s = rest[end + 1..].trim_start();
+    }
+
+    if kind.map(|v| v != "text/css").unwrap_or(false) || alternate == Some("yes") {
+        return None;
+    }
+
+    if !css::media_query_list_matches(media.unwrap_or(""), opt) {
+        return None;
+    }
+
+    href
+}
+
+fn resolve_css<'a>(sources: &'a [Cow<str>], opt: &'a Options) -> StyleSheet<'a> {
+    let mut sheet = StyleSheet::new();
+    for text in sources {
+        sheet.parse_more(text, Origin::Author, opt);
+    }
+
+    if let Some(ref text) = opt.user_stylesheet {
+        sheet.parse_more(text, Origin::User, opt);
     }
 
     sheet
@@ -1339,7 +2046,8 @@ fn find_recursive_pattern(
     aid: AId,
     doc: &mut Document,
 ) -> Option<NodeId> {
-    for pattern_node in doc.root().descendants().filter(|n| n.has_tag_name(EId::Pattern)) {
+    let is_pattern = |n: &Node| n.has_tag_name(EId::Pattern) || n.has_tag_name(EId::Hatch);
+    for pattern_node in doc.root().descendants().filter(is_pattern) {
         for node in pattern_node.descendants() {
             if let Some(&AttributeValue::Paint(ref link_id, _)) = node.attribute(aid) {
                 if link_id == pattern_node.element_id() {
@@ -1382,7 +2090,7 @@ fn find_recursive_link(
 ) -> Option<NodeId> {
     for node in doc.root().descendants().filter(|n| n.has_tag_name(eid)) {
         for child in node.descendants() {
-            if let Some(link) = child.attribute::<Node>(aid) {
+            for link in attribute_links(child, aid) {
                 if link == node {
                     // If an element child has a link to the element itself
                     // then we have to replace it with `none`.
@@ -1391,10 +2099,8 @@ fn find_recursive_link(
                 } else {
                     // Check that linked node children doesn't link this element.
                     for node2 in link.descendants() {
-                        if let Some(link2) = node2.attribute::<Node>(aid) {
-                            if link2 == node {
-                                return Some(node2.id);
-                            }
+                        if attribute_links(node2, aid).contains(&node) {
+                            return Some(node2.id);
                         }
                     }
                 }
@@ -1404,3 +2110,18 @@ fn find_recursive_link(
 
     None
 }
+
+/// Returns all elements referenced by the attribute.
+///
+/// Unlike other attributes, `filter` can reference multiple elements.
+fn attribute_links<'a>(node: Node<'a>, aid: AId) -> Vec<Node<'a>> {
+    match node.attribute::<&AttributeValue>(aid) {
+        Some(AttributeValue::Filters(ref list)) => {
+            list.iter().filter_map(|v| match v {
+                FilterValue::Url(ref id) => node.document().element_by_id(id),
+                FilterValue::Function(_) => None,
+            }).collect()
+        }
+        _ => node.attribute::<Node>(aid).into_iter().collect(),
+    }
+}
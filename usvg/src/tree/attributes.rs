@@ -123,6 +123,24 @@ impl_enum_from_str!(SpreadMethod,
 );
 
 
+/// A mesh gradient color interpolation.
+///
+/// `type` attribute of the `meshgradient` element in the SVG.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MeshInterpolation {
+    Bilinear,
+    Bicubic,
+}
+
+impl_enum_default!(MeshInterpolation, Bilinear);
+
+impl_enum_from_str!(MeshInterpolation,
+    "bilinear"  => MeshInterpolation::Bilinear,
+    "bicubic"   => MeshInterpolation::Bicubic
+);
+
+
 /// A visibility property.
 ///
 /// `visibility` attribute in the SVG.
@@ -143,6 +161,130 @@ impl_enum_from_str!(Visibility,
 );
 
 
+/// A vector effect.
+///
+/// `vector-effect` attribute in the SVG.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum VectorEffect {
+    None,
+    NonScalingStroke,
+}
+
+impl_enum_default!(VectorEffect, None);
+
+impl_enum_from_str!(VectorEffect,
+    "none"                  => VectorEffect::None,
+    "non-scaling-stroke"    => VectorEffect::NonScalingStroke
+);
+
+
+/// A mask type.
+///
+/// `mask-type` attribute in the SVG.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MaskType {
+    /// The mask content luminance is used as a mask value.
+    Luminance,
+    /// The mask content alpha channel is used as a mask value.
+    Alpha,
+}
+
+impl_enum_default!(MaskType, Luminance);
+
+impl_enum_from_str!(MaskType,
+    "luminance" => MaskType::Luminance,
+    "alpha"     => MaskType::Alpha
+);
+
+
+/// A paint order item.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PaintOrderKind {
+    Fill,
+    Stroke,
+    Markers,
+}
+
+
+/// A paint order.
+///
+/// `paint-order` attribute in the SVG.
+///
+/// Always contains all three items, in the order they should be painted.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PaintOrder {
+    /// Items in the painting order.
+    pub order: [PaintOrderKind; 3],
+}
+
+impl PaintOrder {
+    /// Checks that the stroke should be painted before the fill.
+    pub fn is_stroke_first(&self) -> bool {
+        self.position(PaintOrderKind::Stroke) < self.position(PaintOrderKind::Fill)
+    }
+
+    fn position(&self, kind: PaintOrderKind) -> usize {
+        self.order.iter().position(|k| *k == kind).unwrap_or(0)
+    }
+}
+
+impl Default for PaintOrder {
+    fn default() -> Self {
+        PaintOrder {
+            order: [PaintOrderKind::Fill, PaintOrderKind::Stroke, PaintOrderKind::Markers],
+        }
+    }
+}
+
+
+/// A blending mode.
+///
+/// `mix-blend-mode` attribute in the SVG.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BlendMode {
+    Normal,
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+impl_enum_default!(BlendMode, Normal);
+
+impl_enum_from_str!(BlendMode,
+    "normal"        => BlendMode::Normal,
+    "multiply"      => BlendMode::Multiply,
+    "screen"        => BlendMode::Screen,
+    "overlay"       => BlendMode::Overlay,
+    "darken"        => BlendMode::Darken,
+    "lighten"       => BlendMode::Lighten,
+    "color-dodge"   => BlendMode::ColorDodge,
+    "color-burn"    => BlendMode::ColorBurn,
+    "hard-light"    => BlendMode::HardLight,
+    "soft-light"    => BlendMode::SoftLight,
+    "difference"    => BlendMode::Difference,
+    "exclusion"     => BlendMode::Exclusion,
+    "hue"           => BlendMode::Hue,
+    "saturation"    => BlendMode::Saturation,
+    "color"         => BlendMode::Color,
+    "luminosity"    => BlendMode::Luminosity
+);
+
+
 /// A paint style.
 ///
 /// `paint` value type in the SVG.
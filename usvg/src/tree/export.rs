@@ -65,6 +65,9 @@ fn conv_defs(
                 write_base_grad(&rg.base, xml);
                 xml.end_element();
             }
+            NodeKind::MeshGradient(ref mg) => {
+                write_mesh_grad(mg, xml);
+            }
             NodeKind::ClipPath(ref clip) => {
                 xml.start_svg_element(EId::ClipPath);
                 xml.write_svg_attribute(AId::Id, &clip.id);
@@ -86,6 +89,10 @@ fn conv_defs(
                 xml.write_units(AId::MaskContentUnits, mask.content_units, Units::UserSpaceOnUse);
                 xml.write_rect_attrs(mask.rect);
 
+                if mask.kind == MaskType::Alpha {
+                    xml.write_svg_attribute(AId::MaskType, "alpha");
+                }
+
                 if let Some(ref id) = mask.mask {
                     xml.write_func_iri(AId::Mask, id);
                 }
@@ -110,6 +117,9 @@ fn conv_defs(
 
                 xml.end_element();
             }
+            NodeKind::Hatch(ref hatch) => {
+                write_hatch(hatch, xml);
+            }
             NodeKind::Filter(ref filter) => {
                 xml.start_svg_element(EId::Filter);
                 xml.write_svg_attribute(AId::Id, &filter.id);
@@ -162,6 +172,21 @@ fn conv_defs(
                             xml.write_svg_attribute(AId::Result, &fe.result);
                             xml.end_element();
                         }
+                        FilterKind::FeDropShadow(ref shadow) => {
+                            xml.start_svg_element(EId::FeDropShadow);
+                            xml.write_filter_primitive_attrs(fe);
+                            xml.write_filter_input(AId::In, &shadow.input);
+                            xml.write_attribute_fmt(
+                                AId::StdDeviation.to_str(),
+                                format_args!("{} {}", shadow.std_dev_x.value(), shadow.std_dev_y.value()),
+                            );
+                            xml.write_svg_attribute(AId::Dx, &shadow.dx);
+                            xml.write_svg_attribute(AId::Dy, &shadow.dy);
+                            xml.write_svg_attribute(AId::FloodColor, &shadow.color);
+                            xml.write_svg_attribute(AId::FloodOpacity, &shadow.opacity.value());
+                            xml.write_svg_attribute(AId::Result, &fe.result);
+                            xml.end_element();
+                        }
                         FilterKind::FeComposite(ref composite) => {
                             xml.start_svg_element(EId::FeComposite);
                             xml.write_filter_primitive_attrs(fe);
@@ -462,8 +487,9 @@ fn conv_element(
                 xml.write_func_iri(AId::Mask, id);
             }
 
-            if let Some(ref id) = g.filter {
-                xml.write_func_iri(AId::Filter, id);
+            if !g.filters.is_empty() {
+                let links: Vec<String> = g.filters.iter().map(|id| format!("url(#{})", id)).collect();
+                xml.write_svg_attribute(AId::Filter, &links.join(" "));
 
                 if let Some(ref fill) = g.filter_fill {
                     write_paint(AId::Fill, fill, xml);
@@ -478,6 +504,31 @@ fn conv_element(
                 xml.write_svg_attribute(AId::Opacity, &g.opacity.value());
             }
 
+            if g.blend_mode != BlendMode::Normal {
+                xml.write_svg_attribute(AId::MixBlendMode, match g.blend_mode {
+                    BlendMode::Normal       => "normal",
+                    BlendMode::Multiply     => "multiply",
+                    BlendMode::Screen       => "screen",
+                    BlendMode::Overlay      => "overlay",
+                    BlendMode::Darken       => "darken",
+                    BlendMode::Lighten      => "lighten",
+                    BlendMode::ColorDodge   => "color-dodge",
+                    BlendMode::ColorBurn    => "color-burn",
+                    BlendMode::HardLight    => "hard-light",
+                    BlendMode::SoftLight    => "soft-light",
+                    BlendMode::Difference   => "difference",
+                    BlendMode::Exclusion    => "exclusion",
+                    BlendMode::Hue          => "hue",
+                    BlendMode::Saturation   => "saturation",
+                    BlendMode::Color        => "color",
+                    BlendMode::Luminosity   => "luminosity",
+                });
+            }
+
+            if g.isolate {
+                xml.write_svg_attribute(AId::Isolation, "isolate");
+            }
+
             xml.write_transform(AId::Transform, g.transform);
 
             if let Some(eb) = g.enable_background {
@@ -721,6 +772,13 @@ fn write_base_grad(
         SpreadMethod::Repeat => xml.write_svg_attribute(AId::SpreadMethod, "repeat"),
     }
 
+    match g.color_interpolation {
+        ColorInterpolation::SRGB => {},
+        ColorInterpolation::LinearRGB => {
+            xml.write_svg_attribute(AId::ColorInterpolation, "linearRGB")
+        }
+    }
+
     for s in &g.stops {
         xml.start_svg_element(EId::Stop);
         xml.write_svg_attribute(AId::Offset, &s.offset.value());
@@ -733,6 +791,94 @@ fn write_base_grad(
     }
 }
 
+fn write_mesh_grad(
+    mg: &MeshGradient,
+    xml: &mut XmlWriter,
+) {
+    xml.start_svg_element(EId::Meshgradient);
+    xml.write_svg_attribute(AId::Id, &mg.id);
+
+    if let Some(patch) = mg.rows.first().and_then(|row| row.first()) {
+        xml.write_svg_attribute(AId::X, &patch.points[0].0);
+        xml.write_svg_attribute(AId::Y, &patch.points[0].1);
+    }
+
+    xml.write_units(AId::GradientUnits, mg.units, Units::ObjectBoundingBox);
+    xml.write_transform(AId::GradientTransform, mg.transform);
+
+    if mg.interpolation == MeshInterpolation::Bicubic {
+        xml.write_svg_attribute(AId::Type, "bicubic");
+    }
+
+    for (row_idx, row) in mg.rows.iter().enumerate() {
+        xml.start_svg_element(EId::Meshrow);
+        for (col_idx, patch) in row.iter().enumerate() {
+            xml.start_svg_element(EId::Meshpatch);
+
+            // Shared edges are defined only by the first patch that uses them.
+            let edges: &[usize] = match (row_idx, col_idx) {
+                (0, 0) => &[0, 1, 2, 3],
+                (0, _) => &[0, 1, 2],
+                (_, 0) => &[1, 2, 3],
+                (_, _) => &[1, 2],
+            };
+
+            for &edge in edges {
+                let p = &patch.points;
+                let (p1, p2, p3) = (p[edge * 3 + 1], p[edge * 3 + 2], p[(edge * 3 + 3) % 12]);
+
+                xml.start_svg_element(EId::Stop);
+                xml.write_attribute_fmt(
+                    AId::Path.to_str(),
+                    format_args!("C {} {} {} {} {} {}", p1.0, p1.1, p2.0, p2.1, p3.0, p3.1),
+                );
+                xml.write_svg_attribute(AId::StopColor, &patch.colors[edge]);
+                if !patch.opacities[edge].is_default() {
+                    xml.write_svg_attribute(AId::StopOpacity, &patch.opacities[edge].value());
+                }
+
+                xml.end_element();
+            }
+
+            xml.end_element();
+        }
+
+        xml.end_element();
+    }
+
+    xml.end_element();
+}
+
+fn write_hatch(
+    hatch: &Hatch,
+    xml: &mut XmlWriter,
+) {
+    xml.start_svg_element(EId::Hatch);
+    xml.write_svg_attribute(AId::Id, &hatch.id);
+    xml.write_svg_attribute(AId::X, &hatch.x);
+    xml.write_svg_attribute(AId::Y, &hatch.y);
+    xml.write_svg_attribute(AId::Pitch, &hatch.pitch.value());
+    xml.write_svg_attribute(AId::Rotate, &hatch.rotate);
+    xml.write_units(AId::HatchUnits, hatch.units, Units::ObjectBoundingBox);
+    xml.write_units(AId::HatchContentUnits, hatch.content_units, Units::UserSpaceOnUse);
+    xml.write_transform(AId::Transform, hatch.transform);
+
+    for path in &hatch.paths {
+        xml.start_svg_element(EId::Hatchpath);
+        xml.write_svg_attribute(AId::Offset, &path.offset);
+
+        write_stroke(&path.stroke, xml);
+
+        if let Some(ref data) = path.data {
+            write_path_data(data, xml);
+        }
+
+        xml.end_element();
+    }
+
+    xml.end_element();
+}
+
 fn write_path(
     path: &Path,
     is_clip_path: bool,
@@ -747,6 +893,26 @@ fn write_path(
     write_fill(&path.fill, is_clip_path, xml);
     write_stroke(&path.stroke, xml);
 
+    if path.paint_order != PaintOrder::default() {
+        xml.write_attribute_raw(AId::PaintOrder.to_str(), |buf| {
+            for (i, kind) in path.paint_order.order.iter().enumerate() {
+                if i != 0 {
+                    buf.push(b' ');
+                }
+
+                buf.extend_from_slice(match kind {
+                    PaintOrderKind::Fill => b"fill",
+                    PaintOrderKind::Stroke => b"stroke",
+                    PaintOrderKind::Markers => b"markers",
+                });
+            }
+        });
+    }
+
+    if path.vector_effect == VectorEffect::NonScalingStroke {
+        xml.write_svg_attribute(AId::VectorEffect, "non-scaling-stroke");
+    }
+
     xml.write_visibility(path.visibility);
 
     match path.rendering_mode {
@@ -765,8 +931,17 @@ fn write_path(
 
     xml.write_transform(AId::Transform, path.transform);
 
+    write_path_data(&path.data, xml);
+
+    xml.end_element();
+}
+
+fn write_path_data(
+    data: &PathData,
+    xml: &mut XmlWriter,
+) {
     xml.write_attribute_raw("d", |buf| {
-        for seg in path.data.iter() {
+        for seg in data.iter() {
             match *seg {
                 PathSegment::MoveTo { x, y } => {
                     buf.extend_from_slice(b"M ");
@@ -803,12 +978,10 @@ fn write_path(
             }
         }
 
-        if !path.data.is_empty() {
+        if !data.is_empty() {
             buf.pop();
         }
     });
-
-    xml.end_element();
 }
 
 fn write_fill(
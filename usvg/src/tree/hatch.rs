@@ -0,0 +1,204 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A hatch to pattern conversion.
+
+use std::rc::Rc;
+
+use crate::geom::*;
+use crate::utils::{f64_bound, ts_scale};
+use crate::IsValidLength;
+use super::attributes::*;
+use super::nodes::{Hatch, Path};
+use super::pathdata::{PathData, PathSegment};
+
+/// A maximum amount of hatch path copies along each axis.
+const MAX_COPIES: f64 = 1000.0;
+
+/// A maximum amount of hatch path copies in a tile.
+const MAX_PATHS: usize = 10_000;
+
+/// A maximum tile height in repetitions of the longest hatch path.
+const MAX_PERIODS: u32 = 100;
+
+
+/// A hatch pattern tile.
+#[derive(Clone, Debug)]
+pub struct HatchTile {
+    /// Tile rectangle in the user space.
+    pub rect: Rect,
+
+    /// Tile transform.
+    ///
+    /// Has the same meaning as `Pattern::transform`.
+    pub transform: Transform,
+
+    /// Tile content.
+    ///
+    /// Coordinates are relative to the tile origin and paths transforms
+    /// are not applied to the data, just like in a `Pattern` content.
+    pub paths: Vec<Path>,
+}
+
+impl Hatch {
+    /// Converts the hatch into an equivalent pattern tile.
+    ///
+    /// The tile is `pitch` wide and high enough to contain a whole number
+    /// of repetitions of each hatch path. It should be rendered and repeated just like a `Pattern`
+    /// with `userSpaceOnUse` units, `rect` and `transform` taken from the tile.
+    ///
+    /// `bbox` is the bounding box of the painted element.
+    ///
+    /// Returns `None` when the tile is invalid or requires too many path copies.
+    pub fn tile(&self, bbox: Rect) -> Option<HatchTile> {
+        let (x, y, pitch) = if self.units == Units::ObjectBoundingBox {
+            (
+                bbox.x() + self.x * bbox.width(),
+                bbox.y() + self.y * bbox.height(),
+                self.pitch.value() * bbox.width(),
+            )
+        } else {
+            (self.x, self.y, self.pitch.value())
+        };
+
+        let content_ts = if self.content_units == Units::ObjectBoundingBox {
+            Transform::new_scale(bbox.width(), bbox.height())
+        } else {
+            Transform::default()
+        };
+        let (_, content_sy) = content_ts.get_scale();
+
+        // Each path must be repeated a whole number of times inside the tile,
+        // otherwise it will be cut on the tile edges.
+        let advances: Vec<f64> = self.paths.iter()
+            .filter_map(|path| path.data.as_ref())
+            .map(|data| y_advance(data) * content_sy)
+            .filter(|n| n.is_valid_length())
+            .collect();
+        let mut height = common_multiple(&advances);
+
+        // Infinite lines can be repeated by any distance.
+        if !height.is_valid_length() {
+            height = pitch;
+        }
+
+        let rect = Rect::new(x, y, pitch, height)?;
+
+        let mut transform = self.transform;
+        transform.rotate_at(self.rotate, x, y);
+
+        let mut paths = Vec::new();
+        for path in &self.paths {
+            let stroke = match path.stroke {
+                Some(ref stroke) => stroke,
+                None => continue,
+            };
+
+            let (data, advance) = match path.data {
+                Some(ref data) => (data.clone(), y_advance(data) * content_sy),
+                None => {
+                    // Replace an infinite line with a line that crosses the whole tile.
+                    let margin = height + stroke.width.value() * content_sy;
+                    let mut data = PathData::with_capacity(2);
+                    data.push_move_to(0.0, -margin / content_sy);
+                    data.push_line_to(0.0, (height + margin) / content_sy);
+                    (Rc::new(data), 0.0)
+                }
+            };
+
+            let mut path_ts = content_ts;
+            path_ts.translate(path.offset, 0.0);
+
+            // The stroke is scaled by the content units.
+            let mut bbox_stroke = stroke.clone();
+            bbox_stroke.width = StrokeWidth::new(stroke.width.value() * ts_scale(path_ts));
+            let path_bbox = match data.bbox_with_transform(path_ts, Some(&bbox_stroke)) {
+                Some(bbox) => bbox,
+                None => continue,
+            };
+
+            // Copies that are intersecting the tile.
+            let (x_start, x_end) = copies_range(path_bbox.x(), path_bbox.right(), pitch, pitch);
+            let (y_start, y_end) = if advance.is_valid_length() {
+                copies_range(path_bbox.y(), path_bbox.bottom(), height, advance)
+            } else {
+                (0, 0)
+            };
+
+            // Each copy is a separate path, so their amount must be limited.
+            let count = (x_end - x_start + 1) as usize * (y_end - y_start + 1) as usize;
+            if paths.len() + count > MAX_PATHS {
+                return None;
+            }
+
+            for i in x_start..=x_end {
+                for j in y_start..=y_end {
+                    let mut ts = Transform::new_translate(i as f64 * pitch, j as f64 * advance);
+                    ts.append(&path_ts);
+
+                    paths.push(Path {
+                        transform: ts,
+                        stroke: Some(stroke.clone()),
+                        data: data.clone(),
+                        ..Path::default()
+                    });
+                }
+            }
+        }
+
+        Some(HatchTile { rect, transform, paths })
+    }
+}
+
+/// Returns the least common multiple of the repetition distances.
+///
+/// Distances are not integers, so a multiple is searched only up to `MAX_PERIODS`
+/// repetitions of the longest one. The longest distance is used when nothing was found.
+fn common_multiple(advances: &[f64]) -> f64 {
+    let max = advances.iter().cloned().fold(0.0, f64::max);
+    if max.is_fuzzy_zero() {
+        return 0.0;
+    }
+
+    for i in 1..=MAX_PERIODS {
+        let height = max * i as f64;
+        let is_multiple = advances.iter().all(|n| {
+            let count = height / n;
+            (count - count.round()).abs() < 1e-6 * count.max(1.0)
+        });
+
+        if is_multiple {
+            return height;
+        }
+    }
+
+    max
+}
+
+/// Returns a distance between the first and the last path points along the Y axis.
+fn y_advance(data: &PathData) -> f64 {
+    let first = data.iter().find_map(segment_y);
+    let last = data.iter().rev().find_map(segment_y);
+    match (first, last) {
+        (Some(first), Some(last)) => (last - first).abs(),
+        _ => 0.0,
+    }
+}
+
+fn segment_y(seg: &PathSegment) -> Option<f64> {
+    match *seg {
+        PathSegment::MoveTo { y, .. } => Some(y),
+        PathSegment::LineTo { y, .. } => Some(y),
+        PathSegment::CurveTo { y, .. } => Some(y),
+        PathSegment::ClosePath => None,
+    }
+}
+
+/// Returns the range of copies, shifted by `step`, that are intersecting
+/// the `0..size` range with an object located at `start..end`.
+fn copies_range(start: f64, end: f64, size: f64, step: f64) -> (i32, i32) {
+    let first = f64_bound(-MAX_COPIES, (-end / step).ceil(), 0.0);
+    let last = f64_bound(0.0, ((size - start) / step).floor(), MAX_COPIES);
+    (first as i32, last as i32)
+}
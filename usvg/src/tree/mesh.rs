@@ -0,0 +1,281 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A mesh gradient rasterizer.
+
+use crate::geom::*;
+use crate::utils::f64_bound;
+use crate::TransformFromBBox;
+use super::attributes::*;
+use super::nodes::{MeshGradient, MeshPatch};
+
+/// A maximum amount of cells per patch side.
+const MAX_SUBDIVISIONS: f64 = 64.0;
+
+/// A preferred cell size in pixels.
+const CELL_SIZE: f64 = 4.0;
+
+/// A maximum image width and height in pixels.
+const MAX_IMAGE_SIZE: f64 = 8192.0;
+
+
+/// A rasterized mesh gradient.
+#[derive(Clone, Debug)]
+pub struct MeshImage {
+    /// Image size.
+    pub size: ScreenSize,
+
+    /// Non-premultiplied RGBA8 pixels.
+    pub data: Vec<u8>,
+
+    /// A transform from the image pixels to the user space.
+    pub transform: Transform,
+}
+
+impl MeshGradient {
+    /// Rasterizes the gradient into an image.
+    ///
+    /// The image covers the shape bounding box expanded by `margin` on each side,
+    /// so everything painted by the gradient would be inside the image.
+    /// `margin` should be zero for fills and large enough to include a stroke for strokes.
+    ///
+    /// `ts` is the current canvas transform, which defines the image resolution.
+    /// `canvas` is the canvas size in pixels. Only the visible part of the gradient
+    /// is rasterized.
+    ///
+    /// Pixels outside of patches are transparent.
+    ///
+    /// Returns `None` when the visible part is empty or larger than 8192x8192 pixels.
+    pub fn rasterize(
+        &self,
+        bbox: Rect,
+        margin: f64,
+        ts: Transform,
+        canvas: ScreenSize,
+        opacity: Opacity,
+    ) -> Option<MeshImage> {
+        let rect = Rect::new(
+            bbox.x() - margin,
+            bbox.y() - margin,
+            bbox.width() + margin * 2.0,
+            bbox.height() + margin * 2.0,
+        )?;
+
+        // Clip the image to the canvas, which is mapped back to the user space.
+        let canvas_rect = canvas.to_size().to_rect(0.0, 0.0).transform(&invert(ts)?)?;
+        let x = rect.x().max(canvas_rect.x());
+        let y = rect.y().max(canvas_rect.y());
+        let rect = Rect::new(
+            x,
+            y,
+            rect.right().min(canvas_rect.right()) - x,
+            rect.bottom().min(canvas_rect.bottom()) - y,
+        )?;
+
+        let (sx, sy) = ts.get_scale();
+        let width = (rect.width() * sx).ceil();
+        let height = (rect.height() * sy).ceil();
+        if !(width <= MAX_IMAGE_SIZE && height <= MAX_IMAGE_SIZE) {
+            return None;
+        }
+
+        let size = ScreenSize::new(width as u32, height as u32)?;
+        let len = (size.width() as usize)
+            .checked_mul(size.height() as usize)?
+            .checked_mul(4)?;
+
+        let mut grad_ts = Transform::new_scale(sx, sy);
+        grad_ts.translate(-rect.x(), -rect.y());
+        if self.units == Units::ObjectBoundingBox {
+            grad_ts.append(&Transform::from_bbox(bbox));
+        }
+        grad_ts.append(&self.transform);
+
+        let mut data = vec![0; len];
+        for row in &self.rows {
+            for patch in row {
+                draw_patch(patch, grad_ts, opacity, size, &mut data);
+            }
+        }
+
+        let mut transform = Transform::new_translate(rect.x(), rect.y());
+        transform.scale(1.0 / sx, 1.0 / sy);
+
+        Some(MeshImage { size, data, transform })
+    }
+}
+
+fn invert(ts: Transform) -> Option<Transform> {
+    let det = ts.a * ts.d - ts.b * ts.c;
+    if det.is_fuzzy_zero() {
+        return None;
+    }
+
+    Some(Transform::new(
+        ts.d / det,
+        -ts.b / det,
+        -ts.c / det,
+        ts.a / det,
+        (ts.c * ts.f - ts.d * ts.e) / det,
+        (ts.b * ts.e - ts.a * ts.f) / det,
+    ))
+}
+
+
+#[derive(Clone, Copy)]
+struct Vertex {
+    x: f64,
+    y: f64,
+    color: [f64; 4],
+}
+
+/// Renders a Coons patch by subdividing it into Gouraud-shaded triangles.
+fn draw_patch(
+    patch: &MeshPatch,
+    ts: Transform,
+    opacity: Opacity,
+    size: ScreenSize,
+    data: &mut [u8],
+) {
+    let mut points = patch.points;
+    for p in points.iter_mut() {
+        *p = ts.apply(p.0, p.1);
+    }
+
+    // Control polygons are always longer than curves,
+    // so we can use them to estimate a patch size.
+    let mut max_len = 0.0f64;
+    for edge in 0..4 {
+        let mut len = 0.0;
+        for i in 0..3 {
+            let p1 = points[edge * 3 + i];
+            let p2 = points[(edge * 3 + i + 1) % 12];
+            len += Line::new(p1.0, p1.1, p2.0, p2.1).length();
+        }
+
+        max_len = max_len.max(len);
+    }
+
+    let n = f64_bound(1.0, (max_len / CELL_SIZE).ceil(), MAX_SUBDIVISIONS) as usize;
+
+    let mut colors = [[0.0; 4]; 4];
+    for (i, c) in colors.iter_mut().enumerate() {
+        let color = patch.colors[i];
+        *c = [
+            color.red as f64,
+            color.green as f64,
+            color.blue as f64,
+            patch.opacities[i].value() * opacity.value() * 255.0,
+        ];
+    }
+
+    let mut grid = Vec::with_capacity((n + 1) * (n + 1));
+    for j in 0..=n {
+        let v = j as f64 / n as f64;
+        for i in 0..=n {
+            let u = i as f64 / n as f64;
+            let (x, y) = coons_point(&points, u, v);
+
+            let mut color = [0.0; 4];
+            for (k, c) in color.iter_mut().enumerate() {
+                *c =  (1.0 - u) * (1.0 - v) * colors[0][k]
+                    + u * (1.0 - v) * colors[1][k]
+                    + u * v * colors[2][k]
+                    + (1.0 - u) * v * colors[3][k];
+            }
+
+            grid.push(Vertex { x, y, color });
+        }
+    }
+
+    for j in 0..n {
+        for i in 0..n {
+            let v1 = grid[j * (n + 1) + i];
+            let v2 = grid[j * (n + 1) + i + 1];
+            let v3 = grid[(j + 1) * (n + 1) + i + 1];
+            let v4 = grid[(j + 1) * (n + 1) + i];
+
+            fill_triangle(v1, v2, v3, size, data);
+            fill_triangle(v1, v3, v4, size, data);
+        }
+    }
+}
+
+/// Evaluates a Coons patch surface at the specified parametric position.
+fn coons_point(p: &[(f64, f64); 12], u: f64, v: f64) -> (f64, f64) {
+    let top = cubic_point(p[0], p[1], p[2], p[3], u);
+    let right = cubic_point(p[3], p[4], p[5], p[6], v);
+    let bottom = cubic_point(p[9], p[8], p[7], p[6], u);
+    let left = cubic_point(p[0], p[11], p[10], p[9], v);
+
+    let coord = |f: fn((f64, f64)) -> f64| {
+        let curves = (1.0 - v) * f(top) + v * f(bottom) + (1.0 - u) * f(left) + u * f(right);
+        let corners =  (1.0 - u) * (1.0 - v) * f(p[0])
+                     + u * (1.0 - v) * f(p[3])
+                     + u * v * f(p[6])
+                     + (1.0 - u) * v * f(p[9]);
+        curves - corners
+    };
+
+    (coord(|p| p.0), coord(|p| p.1))
+}
+
+fn cubic_point(p0: (f64, f64), p1: (f64, f64), p2: (f64, f64), p3: (f64, f64), t: f64) -> (f64, f64) {
+    let mt = 1.0 - t;
+    let a = mt * mt * mt;
+    let b = 3.0 * mt * mt * t;
+    let c = 3.0 * mt * t * t;
+    let d = t * t * t;
+
+    (
+        a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0,
+        a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1,
+    )
+}
+
+/// Fills a triangle with colors interpolated between its vertices.
+///
+/// Pixels are replaced and not blended, so shared edges would not be visible.
+fn fill_triangle(v0: Vertex, v1: Vertex, v2: Vertex, size: ScreenSize, data: &mut [u8]) {
+    // Allow a small error, so there will be no gaps between triangles.
+    const EPSILON: f64 = 1e-6;
+
+    let area = edge_function(v0, v1, v2.x, v2.y);
+    if area.abs() < EPSILON {
+        return;
+    }
+
+    let min_x = v0.x.min(v1.x).min(v2.x).floor().max(0.0);
+    let min_y = v0.y.min(v1.y).min(v2.y).floor().max(0.0);
+    let max_x = v0.x.max(v1.x).max(v2.x).ceil().min(size.width() as f64 - 1.0);
+    let max_y = v0.y.max(v1.y).max(v2.y).ceil().min(size.height() as f64 - 1.0);
+    if min_x > max_x || min_y > max_y {
+        return;
+    }
+
+    for y in min_y as u32..=max_y as u32 {
+        for x in min_x as u32..=max_x as u32 {
+            // Sample at the pixel center.
+            let px = x as f64 + 0.5;
+            let py = y as f64 + 0.5;
+
+            let w0 = edge_function(v1, v2, px, py) / area;
+            let w1 = edge_function(v2, v0, px, py) / area;
+            let w2 = 1.0 - w0 - w1;
+            if w0 < -EPSILON || w1 < -EPSILON || w2 < -EPSILON {
+                continue;
+            }
+
+            let idx = (y as usize * size.width() as usize + x as usize) * 4;
+            for k in 0..4 {
+                let c = w0 * v0.color[k] + w1 * v1.color[k] + w2 * v2.color[k];
+                data[idx + k] = f64_bound(0.0, c.round(), 255.0) as u8;
+            }
+        }
+    }
+}
+
+fn edge_function(a: Vertex, b: Vertex, x: f64, y: f64) -> f64 {
+    (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+}
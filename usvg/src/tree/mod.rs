@@ -7,11 +7,13 @@
 use std::cell::Ref;
 use std::path;
 
-pub use self::{nodes::*, attributes::*, pathdata::*};
+pub use self::{nodes::*, attributes::*, hatch::*, mesh::*, pathdata::*};
 use crate::{svgtree, Rect, Error, Options, XmlOptions};
 
 mod attributes;
 mod export;
+mod hatch;
+mod mesh;
 mod nodes;
 mod numbers;
 mod pathdata;
@@ -53,7 +55,7 @@ impl Tree {
 
     /// Parses `Tree` from the SVG string.
     pub fn from_str(text: &str, opt: &Options) -> Result<Self, Error> {
-        let doc = svgtree::Document::parse(text).map_err(Error::ParsingFailed)?;
+        let doc = svgtree::Document::parse(text, opt).map_err(Error::ParsingFailed)?;
         Self::from_dom(doc, &opt)
     }
 
@@ -183,7 +185,8 @@ pub trait NodeExt {
 
     /// Returns node's paint server units.
     ///
-    /// Returns `None` when node is not a `LinearGradient`, `RadialGradient` or `Pattern`.
+    /// Returns `None` when node is not a `LinearGradient`, `RadialGradient`,
+    /// `MeshGradient`, `Pattern` or `Hatch`.
     fn units(&self) -> Option<Units>;
 
     /// Appends `kind` as a node child.
@@ -233,7 +236,9 @@ impl NodeExt for Node {
         match *self.borrow() {
             NodeKind::LinearGradient(ref lg) => Some(lg.units),
             NodeKind::RadialGradient(ref rg) => Some(rg.units),
+            NodeKind::MeshGradient(ref mg) => Some(mg.units),
             NodeKind::Pattern(ref patt) => Some(patt.units),
+            NodeKind::Hatch(ref hatch) => Some(hatch.units),
             _ => None,
         }
     }
@@ -328,6 +333,8 @@ fn calc_node_bbox(
 
     match *node.borrow() {
         NodeKind::Path(ref path) => {
+            // The stroke width is not affected by the transform here,
+            // which is exactly how a `non-scaling-stroke` works.
             path.data.bbox_with_transform(ts2, path.stroke.as_ref())
         }
         NodeKind::Image(ref img) => {
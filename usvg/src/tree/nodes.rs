@@ -2,10 +2,14 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::borrow::Cow;
 use std::ops::Deref;
 use std::rc::Rc;
 
+use svgtypes::{FuzzyEq, FuzzyZero};
+
 use crate::geom::*;
+use crate::utils::{f64_bound, ts_scale};
 use super::attributes::*;
 use super::pathdata::PathData;
 
@@ -20,9 +24,11 @@ pub enum NodeKind {
     Defs,
     LinearGradient(LinearGradient),
     RadialGradient(RadialGradient),
+    MeshGradient(MeshGradient),
     ClipPath(ClipPath),
     Mask(Mask),
     Pattern(Pattern),
+    Hatch(Hatch),
     Filter(Filter),
     Path(Path),
     Image(Image),
@@ -40,9 +46,11 @@ impl NodeKind {
             NodeKind::Defs => "",
             NodeKind::LinearGradient(ref e) => e.id.as_str(),
             NodeKind::RadialGradient(ref e) => e.id.as_str(),
+            NodeKind::MeshGradient(ref e) => e.id.as_str(),
             NodeKind::ClipPath(ref e) => e.id.as_str(),
             NodeKind::Mask(ref e) => e.id.as_str(),
             NodeKind::Pattern(ref e) => e.id.as_str(),
+            NodeKind::Hatch(ref e) => e.id.as_str(),
             NodeKind::Filter(ref e) => e.id.as_str(),
             NodeKind::Path(ref e) => e.id.as_str(),
             NodeKind::Image(ref e) => e.id.as_str(),
@@ -60,9 +68,11 @@ impl NodeKind {
             NodeKind::Defs => Transform::default(),
             NodeKind::LinearGradient(ref e) => e.transform,
             NodeKind::RadialGradient(ref e) => e.transform,
+            NodeKind::MeshGradient(ref e) => e.transform,
             NodeKind::ClipPath(ref e) => e.transform,
             NodeKind::Mask(_) => Transform::default(),
             NodeKind::Pattern(ref e) => e.transform,
+            NodeKind::Hatch(ref e) => e.transform,
             NodeKind::Filter(_) => Transform::default(),
             NodeKind::Path(ref e) => e.transform,
             NodeKind::Image(ref e) => e.transform,
@@ -118,6 +128,22 @@ pub struct Path {
     /// `shape-rendering` in SVG.
     pub rendering_mode: ShapeRendering,
 
+    /// Fill and stroke painting order.
+    ///
+    /// `paint-order` in SVG.
+    ///
+    /// Markers are already positioned accordingly by `usvg`,
+    /// so only the fill and stroke order should be handled by a renderer.
+    pub paint_order: PaintOrder,
+
+    /// Vector effect.
+    ///
+    /// `vector-effect` in SVG.
+    ///
+    /// Use `Path::stroke_with_transform` to get a stroke
+    /// that should be used with the current canvas transform.
+    pub vector_effect: VectorEffect,
+
     /// Segments list.
     ///
     /// All segments are in absolute coordinates.
@@ -133,11 +159,48 @@ impl Default for Path {
             fill: None,
             stroke: None,
             rendering_mode: ShapeRendering::default(),
+            paint_order: PaintOrder::default(),
+            vector_effect: VectorEffect::default(),
             data: Rc::new(PathData::default()),
         }
     }
 }
 
+impl Path {
+    /// Returns a path stroke adjusted to the specified canvas transform.
+    ///
+    /// The `non-scaling-stroke` width and dashing are defined in the device space,
+    /// so they will be divided by the transform scale.
+    /// Non-uniform scale and skew are approximated by a uniform scale.
+    ///
+    /// Other strokes are returned as is.
+    pub fn stroke_with_transform(&self, ts: Transform) -> Cow<Option<Stroke>> {
+        if self.vector_effect != VectorEffect::NonScalingStroke {
+            return Cow::Borrowed(&self.stroke);
+        }
+
+        let scale = ts_scale(ts);
+        if scale.is_fuzzy_zero() || scale.fuzzy_eq(&1.0) {
+            return Cow::Borrowed(&self.stroke);
+        }
+
+        let mut stroke = match self.stroke {
+            Some(ref stroke) => stroke.clone(),
+            None => return Cow::Borrowed(&self.stroke),
+        };
+
+        stroke.width = StrokeWidth::new(stroke.width.value() / scale);
+        stroke.dashoffset = (stroke.dashoffset as f64 / scale) as f32;
+        if let Some(ref mut list) = stroke.dasharray {
+            for n in list.iter_mut() {
+                *n /= scale;
+            }
+        }
+
+        Cow::Owned(Some(stroke))
+    }
+}
+
 
 /// A raster image element.
 ///
@@ -197,23 +260,36 @@ pub struct Group {
     /// it with a parent group using the specified opacity.
     pub opacity: Opacity,
 
+    /// Group blending mode.
+    ///
+    /// `mix-blend-mode` in SVG.
+    pub blend_mode: BlendMode,
+
+    /// Group isolation.
+    ///
+    /// `isolation` in SVG.
+    pub isolate: bool,
+
     /// Element clip path.
     pub clip_path: Option<String>,
 
     /// Element mask.
     pub mask: Option<String>,
 
-    /// Element filter.
-    pub filter: Option<String>,
+    /// Element filters.
+    ///
+    /// Filters should be applied in order,
+    /// each one using the previous result as `SourceGraphic`.
+    pub filters: Vec<String>,
 
     /// Contains a fill color or paint server used by `FilterInput::FillPaint`.
     ///
-    /// Will be set only when filter actually has a `FilterInput::FillPaint`.
+    /// Will be set only when any filter actually has a `FilterInput::FillPaint`.
     pub filter_fill: Option<Paint>,
 
     /// Contains a fill color or paint server used by `FilterInput::StrokePaint`.
     ///
-    /// Will be set only when filter actually has a `FilterInput::StrokePaint`.
+    /// Will be set only when any filter actually has a `FilterInput::StrokePaint`.
     pub filter_stroke: Option<Paint>,
 
     /// Indicates that this node can be accessed via `filter`.
@@ -228,9 +304,11 @@ impl Default for Group {
             id: String::new(),
             transform: Transform::default(),
             opacity: Opacity::default(),
+            blend_mode: BlendMode::default(),
+            isolate: false,
             clip_path: None,
             mask: None,
-            filter: None,
+            filters: Vec::new(),
             filter_fill: None,
             filter_stroke: None,
             enable_background: None,
@@ -257,10 +335,86 @@ pub struct BaseGradient {
     /// `spreadMethod` in SVG.
     pub spread_method: SpreadMethod,
 
+    /// A color space used for stops interpolation.
+    ///
+    /// `color-interpolation` in SVG.
+    pub color_interpolation: ColorInterpolation,
+
     /// A list of `stop` elements.
     pub stops: Vec<Stop>,
 }
 
+impl BaseGradient {
+    /// Returns stops that should be interpolated in the sRGB color space.
+    ///
+    /// When `color_interpolation` is `LinearRGB`, each stops pair will be expanded
+    /// into multiple stops, so backends that can interpolate colors only in sRGB
+    /// would produce a linearRGB-like ramp.
+    pub fn srgb_stops(&self) -> Cow<[Stop]> {
+        if self.color_interpolation == ColorInterpolation::SRGB {
+            return Cow::Borrowed(&self.stops);
+        }
+
+        // The amount of stops inserted between each stops pair.
+        const STEPS: usize = 16;
+
+        let mut stops = Vec::with_capacity(self.stops.len() * STEPS);
+        for (i, stop) in self.stops.iter().enumerate() {
+            stops.push(*stop);
+
+            let next = match self.stops.get(i + 1) {
+                Some(next) => next,
+                None => break,
+            };
+
+            // Do not expand sharp transitions.
+            if stop.offset == next.offset {
+                continue;
+            }
+
+            let c1 = color_to_linear(stop.color);
+            let c2 = color_to_linear(next.color);
+
+            for step in 1..STEPS {
+                let t = step as f64 / STEPS as f64;
+                let lerp = |a: f64, b: f64| a + (b - a) * t;
+                let channel = |idx: usize| linear_to_srgb(lerp(c1[idx], c2[idx]));
+
+                stops.push(Stop {
+                    offset: lerp(stop.offset.value(), next.offset.value()).into(),
+                    color: Color::new(channel(0), channel(1), channel(2)),
+                    opacity: lerp(stop.opacity.value(), next.opacity.value()).into(),
+                });
+            }
+        }
+
+        Cow::Owned(stops)
+    }
+}
+
+fn color_to_linear(c: Color) -> [f64; 3] {
+    [srgb_to_linear(c.red), srgb_to_linear(c.green), srgb_to_linear(c.blue)]
+}
+
+fn srgb_to_linear(c: u8) -> f64 {
+    let c = c as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f64) -> u8 {
+    let c = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+
+    f64_bound(0.0, (c * 255.0).round(), 255.0) as u8
+}
+
 
 /// A linear gradient.
 ///
@@ -345,6 +499,71 @@ pub struct Stop {
 }
 
 
+/// A mesh gradient.
+///
+/// `meshgradient` element in SVG.
+#[derive(Clone, Debug)]
+pub struct MeshGradient {
+    /// Element's ID.
+    ///
+    /// Taken from the SVG itself.
+    /// Can't be empty.
+    pub id: String,
+
+    /// Coordinate system units.
+    ///
+    /// `gradientUnits` in SVG.
+    pub units: Units,
+
+    /// Gradient transform.
+    ///
+    /// `gradientTransform` in SVG.
+    pub transform: Transform,
+
+    /// Color interpolation type.
+    ///
+    /// `type` in SVG.
+    ///
+    /// Only used during export. Patches are always rendered using bilinear interpolation.
+    pub interpolation: MeshInterpolation,
+
+    /// Mesh patches grouped by rows.
+    ///
+    /// Each row has the same amount of patches.
+    /// Adjacent patches share their edges and corner colors.
+    ///
+    /// `meshrow` elements in SVG.
+    pub rows: Vec<Vec<MeshPatch>>,
+}
+
+
+/// A mesh gradient patch.
+///
+/// A Coons patch, defined by four cubic Bézier edges and four corner colors.
+///
+/// `meshpatch` element in SVG.
+#[derive(Clone, Copy, Debug)]
+pub struct MeshPatch {
+    /// Edges control points.
+    ///
+    /// Edges are stored in the top, right, bottom, left order,
+    /// and each edge starts at the end of the previous one.
+    /// So corners are stored at indexes 0, 3, 6 and 9,
+    /// starting from the top-left one.
+    pub points: [(f64, f64); 12],
+
+    /// Corner colors.
+    ///
+    /// In the top-left, top-right, bottom-right, bottom-left order.
+    pub colors: [Color; 4],
+
+    /// Corner opacities.
+    ///
+    /// In the same order as `colors`.
+    pub opacities: [Opacity; 4],
+}
+
+
 /// A clip-path element.
 ///
 /// `clipPath` element in SVG.
@@ -405,6 +624,11 @@ pub struct Mask {
     /// `maskContentUnits` in SVG.
     pub content_units: Units,
 
+    /// Mask type.
+    ///
+    /// `mask-type` in SVG, or `mask-mode` of the masked element.
+    pub kind: MaskType,
+
     /// Mask rectangle.
     ///
     /// `x`, `y`, `width` and `height` in SVG.
@@ -454,6 +678,88 @@ pub struct Pattern {
 }
 
 
+/// A hatch element.
+///
+/// `hatch` element in SVG.
+///
+/// Use `Hatch::tile` to get an equivalent pattern tile.
+#[derive(Clone, Debug)]
+pub struct Hatch {
+    /// Element's ID.
+    ///
+    /// Taken from the SVG itself.
+    /// Can't be empty.
+    pub id: String,
+
+    /// Coordinate system units.
+    ///
+    /// `hatchUnits` in SVG.
+    pub units: Units,
+
+    /// Content coordinate system units.
+    ///
+    /// `hatchContentUnits` in SVG.
+    pub content_units: Units,
+
+    /// Hatch transform.
+    ///
+    /// `transform` in SVG.
+    pub transform: Transform,
+
+    /// Hatch origin X.
+    ///
+    /// `x` in SVG.
+    pub x: f64,
+
+    /// Hatch origin Y.
+    ///
+    /// `y` in SVG.
+    pub y: f64,
+
+    /// A distance between the hatch paths repetitions.
+    ///
+    /// `pitch` in SVG.
+    pub pitch: PositiveNumber,
+
+    /// Hatch paths rotation in degrees.
+    ///
+    /// `rotate` in SVG.
+    pub rotate: f64,
+
+    /// Hatch paths.
+    ///
+    /// Contains at least one element.
+    ///
+    /// `hatchpath` elements in SVG.
+    pub paths: Vec<HatchPath>,
+}
+
+
+/// A hatch path.
+///
+/// `hatchpath` element in SVG.
+#[derive(Clone, Debug)]
+pub struct HatchPath {
+    /// An offset along the X axis.
+    ///
+    /// `offset` in SVG.
+    pub offset: f64,
+
+    /// Path segments.
+    ///
+    /// Starts at the hatch path origin and repeated along the Y axis
+    /// by the distance between its first and last points.
+    ///
+    /// `None` indicates an infinite vertical line.
+    ///
+    /// `d` in SVG.
+    pub data: Option<Rc<PathData>>,
+
+    /// Stroke style.
+    pub stroke: Option<Stroke>,
+}
+
+
 /// A filter element.
 ///
 /// `filter` element in the SVG.
@@ -526,6 +832,7 @@ pub enum FilterKind {
     FeConvolveMatrix(FeConvolveMatrix),
     FeDiffuseLighting(FeDiffuseLighting),
     FeDisplacementMap(FeDisplacementMap),
+    FeDropShadow(FeDropShadow),
     FeFlood(FeFlood),
     FeGaussianBlur(FeGaussianBlur),
     FeImage(FeImage),
@@ -548,6 +855,7 @@ impl FilterKind {
             FilterKind::FeConvolveMatrix(ref fe) => fe.input == *input,
             FilterKind::FeDiffuseLighting(ref fe) => fe.input == *input,
             FilterKind::FeDisplacementMap(ref fe) => fe.input1 == *input || fe.input2 == *input,
+            FilterKind::FeDropShadow(ref fe) => fe.input == *input,
             FilterKind::FeFlood(_) => false,
             FilterKind::FeGaussianBlur(ref fe) => fe.input == *input,
             FilterKind::FeImage(_) => false,
@@ -799,6 +1107,47 @@ pub struct FeDisplacementMap {
 }
 
 
+/// A drop shadow filter primitive.
+///
+/// This is essentially `feGaussianBlur`, `feOffset`, `feFlood`, `feComposite`
+/// and `feMerge` combined.
+///
+/// `feDropShadow` element in the SVG.
+#[derive(Clone, Debug)]
+pub struct FeDropShadow {
+    /// Identifies input for the given filter primitive.
+    ///
+    /// `in` in the SVG.
+    pub input: FilterInput,
+
+    /// The amount to offset the input graphic along the X-axis.
+    pub dx: f64,
+
+    /// The amount to offset the input graphic along the Y-axis.
+    pub dy: f64,
+
+    /// A standard deviation along the X-axis.
+    ///
+    /// `stdDeviation` in the SVG.
+    pub std_dev_x: PositiveNumber,
+
+    /// A standard deviation along the Y-axis.
+    ///
+    /// `stdDeviation` in the SVG.
+    pub std_dev_y: PositiveNumber,
+
+    /// A flood color.
+    ///
+    /// `flood-color` in the SVG.
+    pub color: Color,
+
+    /// A flood opacity.
+    ///
+    /// `flood-opacity` in the SVG.
+    pub opacity: Opacity,
+}
+
+
 /// A flood filter primitive.
 ///
 /// `feFlood` element in the SVG.
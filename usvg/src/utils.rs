@@ -23,6 +23,23 @@ pub fn f64_bound(min: f64, val: f64, max: f64) -> f64 {
     }
 }
 
+/// Returns a uniform scale factor of the `Transform`.
+///
+/// Non-uniform scale and skew are approximated by a square root of the determinant.
+#[inline]
+pub fn ts_scale(ts: tree::Transform) -> f64 {
+    (ts.a * ts.d - ts.b * ts.c).abs().sqrt()
+}
+
+/// Applies the `Transform` around the specified origin.
+#[inline]
+pub fn transform_around(ts: tree::Transform, x: f64, y: f64) -> tree::Transform {
+    let mut new_ts = tree::Transform::new_translate(x, y);
+    new_ts.append(&ts);
+    new_ts.translate(-x, -y);
+    new_ts
+}
+
 /// Converts `viewBox` to `Transform`.
 pub fn view_box_to_transform(
     view_box: Rect,
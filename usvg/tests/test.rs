@@ -75,6 +75,22 @@ test!(fe_image_with_invalid_link);
 test!(fe_diffuse_lighting_without_light_source);
 test!(fe_specular_lighting_without_light_source);
 test!(fe_specular_lighting_with_invalid_specular_exponent);
+test!(css_cascade);
+test!(css_media);
+test!(gradient_color_interpolation);
+test!(paint_order);
+test!(mix_blend_mode);
+test!(vector_effect);
+test!(transform_origin);
+test!(mesh_gradient);
+test!(hatch);
+test!(context_paint);
+test!(marker_on_shapes);
+test!(fe_drop_shadow);
+test!(filter_functions);
+test!(filter_list);
+test!(mask_type);
+test!(mask_mode);
 // test!(fill_rule_on_text); // `fill-rule` cannot be set on `text`
 // test!(marker_with_visible_overflow); // Marker resolving should not produce a group.
 
@@ -116,6 +132,32 @@ test_size!(size_detection_3,
     usvg::Size::new(5.0, 20.0).unwrap()
 );
 
+test_size!(size_detection_4,
+    "<svg xmlns='http://www.w3.org/2000/svg'><rect x='10' y='20' width='30' height='40'/></svg>",
+    usvg::Size::new(30.0, 40.0).unwrap()
+);
+
+test_size!(size_detection_5,
+    "<svg width='50%' xmlns='http://www.w3.org/2000/svg'><rect width='30' height='40'/></svg>",
+    usvg::Size::new(15.0, 40.0).unwrap()
+);
+
+#[test]
+fn size_detection_6() {
+    use usvg::FuzzyEq;
+
+    let opt = usvg::Options {
+        size_fallback: usvg::SizeFallback::Fixed(usvg::Size::new(200.0, 100.0).unwrap()),
+        .. usvg::Options::default()
+    };
+
+    let tree = usvg::Tree::from_str(
+        "<svg width='50%' xmlns='http://www.w3.org/2000/svg'><rect width='30' height='40'/></svg>",
+        &opt,
+    ).unwrap();
+    assert!(tree.svg_node().size.fuzzy_eq(&usvg::Size::new(100.0, 100.0).unwrap()));
+}
+
 macro_rules! test_size_err {
     ($name:ident, $input:expr) => {
         #[test]
@@ -130,3 +172,555 @@ test_size_err!(size_detection_err_1,
 
 test_size_err!(size_detection_err_2,
     "<svg width='0' height='0' viewBox='0 0 10 20' xmlns='http://www.w3.org/2000/svg'>");
+
+test_size_err!(size_detection_err_3,
+    "<svg xmlns='http://www.w3.org/2000/svg'><rect width='0' height='0'/></svg>");
+
+#[test]
+fn size_detection_err_4() {
+    let opt = usvg::Options {
+        size_fallback: usvg::SizeFallback::Error,
+        .. usvg::Options::default()
+    };
+
+    let tree = usvg::Tree::from_str(
+        "<svg xmlns='http://www.w3.org/2000/svg'><rect width='30' height='40'/></svg>",
+        &opt,
+    );
+    assert!(tree.is_err());
+}
+
+#[test]
+fn css_media_viewport() {
+    let svg = "
+    <svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <style>
+            @media (min-width: 200px) and (orientation: landscape) {
+                rect { fill: #008000 }
+            }
+        </style>
+        <rect width='10' height='10'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    assert!(!tree.to_string(usvg::XmlOptions::default()).contains("#008000"));
+
+    let opt = usvg::Options {
+        viewport_size: Some(usvg::Size::new(300.0, 100.0).unwrap()),
+        .. usvg::Options::default()
+    };
+
+    let tree = usvg::Tree::from_str(svg, &opt).unwrap();
+    assert!(tree.to_string(usvg::XmlOptions::default()).contains("#008000"));
+}
+
+#[test]
+fn css_external() {
+    let opt = usvg::Options {
+        path: Some("tests/files/css-external.svg".into()),
+        load_external_stylesheets: true,
+        .. usvg::Options::default()
+    };
+
+    let svg = std::fs::read_to_string("tests/files/css-external.svg").unwrap();
+    let tree = usvg::Tree::from_str(&svg, &opt).unwrap();
+    let out = tree.to_string(usvg::XmlOptions::default());
+    assert!(out.contains("#008000"));
+    assert!(!out.contains("#ff0000"));
+
+    // Relative paths cannot be resolved without the document path.
+    let no_path_opt = usvg::Options {
+        path: None,
+        .. opt.clone()
+    };
+
+    let tree = usvg::Tree::from_str(&svg, &no_path_opt).unwrap();
+    let out = tree.to_string(usvg::XmlOptions::default());
+    assert_eq!(out.matches("#008000").count(), 1);
+
+    let opt = usvg::Options {
+        load_external_stylesheets: false,
+        .. opt
+    };
+
+    let tree = usvg::Tree::from_str(&svg, &opt).unwrap();
+    let out = tree.to_string(usvg::XmlOptions::default());
+    // Only `#rect2` from the `style` element.
+    assert_eq!(out.matches("#008000").count(), 1);
+    assert!(!out.contains("#ff0000"));
+}
+
+#[test]
+fn css_user_stylesheet() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <style>
+            #rect1 { fill: red }
+        </style>
+        <rect id='rect1' width='10' height='10' style='fill:red'/>
+        <rect class='cls' x='20' width='10' height='10' fill='red'/>
+        <circle id='circle1' cx='50' cy='50' r='10'/>
+    </svg>
+    ";
+
+    let opt = usvg::Options {
+        user_stylesheet: Some("
+            #rect1, .cls { fill: #008000 }
+            rect { stroke: #008000 }
+            #circle1 { display: none }
+        ".to_string()),
+        .. usvg::Options::default()
+    };
+
+    let tree = usvg::Tree::from_str(svg, &opt).unwrap();
+    let out = tree.to_string(usvg::XmlOptions::default());
+    assert_eq!(out.matches("fill=\"#008000\"").count(), 2);
+    assert_eq!(out.matches("stroke=\"#008000\"").count(), 2);
+    assert!(!out.contains("#ff0000"));
+    assert!(tree.node_by_id("circle1").is_none());
+}
+
+#[test]
+fn use_external() {
+    let opt = usvg::Options {
+        path: Some("tests/files/use-external.svg".into()),
+        load_external_use: true,
+        .. usvg::Options::default()
+    };
+
+    let svg = std::fs::read_to_string("tests/files/use-external.svg").unwrap();
+    let tree = usvg::Tree::from_str(&svg, &opt).unwrap();
+    let out = tree.to_string(usvg::XmlOptions::default());
+    assert_eq!(out.matches("<path").count(), 2);
+    assert_eq!(out.matches("fill=\"#008000\"").count(), 2);
+
+    // Relative links cannot be resolved without a document path.
+    let no_path_opt = usvg::Options {
+        path: None,
+        .. opt.clone()
+    };
+
+    let tree = usvg::Tree::from_str(&svg, &no_path_opt).unwrap();
+    let out = tree.to_string(usvg::XmlOptions::default());
+    assert_eq!(out.matches("<path").count(), 0);
+
+    let opt = usvg::Options {
+        load_external_use: false,
+        .. opt
+    };
+
+    let tree = usvg::Tree::from_str(&svg, &opt).unwrap();
+    let out = tree.to_string(usvg::XmlOptions::default());
+    assert_eq!(out.matches("<path").count(), 0);
+}
+
+#[test]
+fn gradient_srgb_stops() {
+    let svg = std::fs::read_to_string("tests/files/gradient-color-interpolation-in.svg").unwrap();
+    let tree = usvg::Tree::from_str(&svg, &usvg::Options::default()).unwrap();
+
+    let stops = |id: &str| -> Vec<usvg::Stop> {
+        match *tree.defs_by_id(id).unwrap().borrow() {
+            usvg::NodeKind::LinearGradient(ref lg) => lg.srgb_stops().to_vec(),
+            _ => unreachable!(),
+        }
+    };
+
+    let stops1 = stops("lg1");
+    assert_eq!(stops1.len(), 17);
+    // A linearRGB midpoint between black and white is lighter than the sRGB one.
+    assert_eq!(stops1[8].offset.value(), 0.5);
+    assert_eq!(stops1[8].color, usvg::Color::new(188, 188, 188));
+
+    assert_eq!(stops("lg2").len(), 2);
+}
+
+#[test]
+fn hatch_tile() {
+    use usvg::FuzzyEq;
+
+    let svg = std::fs::read_to_string("tests/files/hatch-in.svg").unwrap();
+    let tree = usvg::Tree::from_str(&svg, &usvg::Options::default()).unwrap();
+
+    let node = tree.defs_by_id("hatch1").unwrap();
+    let hatch = match *node.borrow() {
+        usvg::NodeKind::Hatch(ref hatch) => hatch.clone(),
+        _ => unreachable!(),
+    };
+
+    let bbox = usvg::Rect::new(20.0, 20.0, 160.0, 160.0).unwrap();
+    let tile = hatch.tile(bbox).unwrap();
+
+    // The tile height is defined by the hatch path repetition.
+    assert!(tile.rect.fuzzy_eq(&usvg::Rect::new(0.0, 0.0, 20.0, 10.0).unwrap()));
+    assert!(tile.transform.fuzzy_eq(&usvg::Transform::new_rotate(45.0)));
+
+    // A line on the tile edge is drawn twice and a zigzag is repeated three times.
+    assert_eq!(tile.paths.len(), 5);
+
+    let node = tree.defs_by_id("hatch2").unwrap();
+    let hatch = match *node.borrow() {
+        usvg::NodeKind::Hatch(ref hatch) => hatch.clone(),
+        _ => unreachable!(),
+    };
+
+    // Paths repeated each 10 and 15 units are matching only each 30 units.
+    let tile = hatch.tile(bbox).unwrap();
+    assert!(tile.rect.fuzzy_eq(&usvg::Rect::new(0.0, 0.0, 20.0, 30.0).unwrap()));
+
+    // A large path with a tiny pitch and advance requires too many copies.
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <hatch id='hatch1' hatchUnits='userSpaceOnUse' pitch='1'>
+            <hatchpath d='M 0 0 L 1000 500 L 0 1' stroke='black'/>
+        </hatch>
+        <rect width='10' height='10' fill='url(#hatch1)'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let node = tree.defs_by_id("hatch1").unwrap();
+    let hatch = match *node.borrow() {
+        usvg::NodeKind::Hatch(ref hatch) => hatch.clone(),
+        _ => unreachable!(),
+    };
+
+    assert!(hatch.tile(usvg::Rect::new(0.0, 0.0, 10.0, 10.0).unwrap()).is_none());
+}
+
+#[test]
+fn non_scaling_stroke_bbox() {
+    use usvg::{FuzzyEq, NodeExt};
+
+    let svg = std::fs::read_to_string("tests/files/vector-effect-in.svg").unwrap();
+    let tree = usvg::Tree::from_str(&svg, &usvg::Options::default()).unwrap();
+
+    // A non-scaling stroke width is not affected by the transform.
+    let bbox = tree.node_by_id("rect1").unwrap().calculate_bbox().unwrap();
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(19.5, 19.5, 41.0, 41.0).unwrap()));
+
+    // A regular stroke width is not scaled either, since it's just an approximation.
+    let bbox = tree.node_by_id("rect2").unwrap().calculate_bbox().unwrap();
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(79.5, 19.5, 41.0, 41.0).unwrap()));
+}
+
+#[test]
+fn mesh_gradient_rasterize() {
+    use usvg::FuzzyEq;
+
+    let svg = std::fs::read_to_string("tests/files/mesh-gradient-in.svg").unwrap();
+    let tree = usvg::Tree::from_str(&svg, &usvg::Options::default()).unwrap();
+
+    let node = tree.defs_by_id("mg1").unwrap();
+    let mesh = match *node.borrow() {
+        usvg::NodeKind::MeshGradient(ref mesh) => mesh.clone(),
+        _ => unreachable!(),
+    };
+
+    let bbox = usvg::Rect::new(20.0, 20.0, 160.0, 160.0).unwrap();
+    let canvas = usvg::ScreenSize::new(200, 200).unwrap();
+    let img = mesh.rasterize(bbox, 20.0, usvg::Transform::default(), canvas, 1.0.into()).unwrap();
+    assert_eq!(img.size, usvg::ScreenSize::new(200, 200).unwrap());
+
+    let pixel = |x: u32, y: u32| {
+        let idx = ((y * 200 + x) * 4) as usize;
+        &img.data[idx..idx + 4]
+    };
+
+    // Colors are interpolated at pixel centers, so they are not exact.
+    let is_near = |x: u32, y: u32, c: [u8; 4]| {
+        pixel(x, y).iter().zip(c.iter()).all(|(a, b)| (*a as i32 - *b as i32).abs() <= 4)
+    };
+
+    // Corners colors.
+    assert!(is_near(20, 20, [255, 0, 0, 255]));
+    assert!(is_near(179, 20, [0, 0, 0, 255]));
+    assert!(is_near(179, 179, [128, 128, 128, 255]));
+    assert!(is_near(20, 179, [255, 0, 255, 255]));
+
+    // A semi-transparent shared corner.
+    assert!(is_near(100, 100, [0, 0, 255, 128]));
+
+    // Outside of the mesh.
+    assert_eq!(pixel(10, 10), &[0, 0, 0, 0]);
+
+    // Only the visible part is rasterized.
+    let ts = usvg::Transform::new(1024.0, 0.0, 0.0, 1024.0, -51200.0, -51200.0);
+    let img = mesh.rasterize(bbox, 20.0, ts, canvas, 1.0.into()).unwrap();
+    assert_eq!(img.size, canvas);
+    assert!(img.transform.fuzzy_eq(&usvg::Transform::new(1.0 / 1024.0, 0.0, 0.0, 1.0 / 1024.0, 50.0, 50.0)));
+
+    // Outside of the canvas.
+    let ts = usvg::Transform::new_translate(-500.0, 0.0);
+    assert!(mesh.rasterize(bbox, 20.0, ts, canvas, 1.0.into()).is_none());
+
+    // Too large.
+    let canvas = usvg::ScreenSize::new(100_000, 100_000).unwrap();
+    let ts = usvg::Transform::new_scale(500.0, 500.0);
+    assert!(mesh.rasterize(bbox, 20.0, ts, canvas, 1.0.into()).is_none());
+}
+
+fn png_with_chunks(chunks: &[(&[u8; 4], &[u8])]) -> Vec<u8> {
+    let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+    for (name, chunk) in chunks {
+        data.extend_from_slice(&(chunk.len() as u32).to_be_bytes());
+        data.extend_from_slice(*name);
+        data.extend_from_slice(chunk);
+        data.extend_from_slice(&[0; 4]); // CRC is not checked
+    }
+
+    data
+}
+
+/// A grayscale ICC profile with a linear curve.
+fn linear_gray_icc() -> Vec<u8> {
+    let mut data = vec![0; 128];
+    data[16..20].copy_from_slice(b"GRAY");
+    data[20..24].copy_from_slice(b"XYZ ");
+    data.extend_from_slice(&1u32.to_be_bytes()); // tags count
+    data.extend_from_slice(b"kTRC");
+    data.extend_from_slice(&144u32.to_be_bytes()); // offset
+    data.extend_from_slice(&14u32.to_be_bytes()); // size
+    data.extend_from_slice(b"curv\0\0\0\0");
+    data.extend_from_slice(&1u32.to_be_bytes());
+    data.extend_from_slice(&256u16.to_be_bytes()); // gamma 1.0
+    data
+}
+
+#[test]
+fn color_profile_png() {
+    // `gAMA` with 1.0 means linear data.
+    let png = png_with_chunks(&[(b"gAMA", &100000u32.to_be_bytes()), (b"IEND", &[])]);
+    let profile = usvg::ColorProfile::from_png(&png).unwrap();
+    let mut pixels = [128, 128, 128, 255];
+    profile.convert_rgba(&mut pixels);
+    assert_eq!(pixels, [188, 188, 188, 255]);
+
+    // `sRGB` has a priority over `gAMA`.
+    let png = png_with_chunks(&[
+        (b"sRGB", &[0]),
+        (b"gAMA", &100000u32.to_be_bytes()),
+        (b"IEND", &[]),
+    ]);
+    assert!(usvg::ColorProfile::from_png(&png).is_none());
+
+    // No color information.
+    let png = png_with_chunks(&[(b"IEND", &[])]);
+    assert!(usvg::ColorProfile::from_png(&png).is_none());
+}
+
+#[test]
+fn color_profile_png_icc() {
+    use std::io::Write;
+
+    fn png_with_icc(icc: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(icc).unwrap();
+
+        let mut chunk = b"test\0\0".to_vec();
+        chunk.extend_from_slice(&encoder.finish().unwrap());
+        png_with_chunks(&[(b"iCCP", &chunk), (b"IEND", &[])])
+    }
+
+    let icc = linear_gray_icc();
+    assert!(usvg::ColorProfile::from_png(&png_with_icc(&icc)).is_some());
+
+    // A profile that decompresses into more than 4 MiB is rejected.
+    let mut icc = icc;
+    icc.resize(5 * 1024 * 1024, 0);
+    assert!(usvg::ColorProfile::from_png(&png_with_icc(&icc)).is_none());
+}
+
+#[test]
+fn color_profile_jpeg() {
+    let icc = linear_gray_icc();
+
+    // Split the profile between two APP2 markers in the reversed order.
+    let mut jpeg = vec![0xff, 0xd8];
+    for (seq, chunk) in [(2u8, &icc[100..]), (1u8, &icc[..100])].iter() {
+        jpeg.extend_from_slice(&[0xff, 0xe2]);
+        jpeg.extend_from_slice(&(chunk.len() as u16 + 16).to_be_bytes());
+        jpeg.extend_from_slice(b"ICC_PROFILE\0");
+        jpeg.extend_from_slice(&[*seq, 2]);
+        jpeg.extend_from_slice(chunk);
+    }
+    jpeg.extend_from_slice(&[0xff, 0xd9]);
+
+    let profile = usvg::ColorProfile::from_jpeg(&jpeg).unwrap();
+    let mut pixels = [0, 64, 128];
+    profile.convert_rgb(&mut pixels);
+    assert_eq!(pixels, [0, 137, 188]);
+}
+
+#[test]
+fn color_profile_invalid_curve() {
+    let mut icc = linear_gray_icc();
+    assert!(usvg::ColorProfile::from_icc(&icc).is_some());
+
+    // A table with 3 entries in a tag that has room only for one.
+    icc[152..156].copy_from_slice(&3u32.to_be_bytes());
+    assert!(usvg::ColorProfile::from_icc(&icc).is_none());
+
+    // An oversized table must not be allocated.
+    icc[152..156].copy_from_slice(&std::u32::MAX.to_be_bytes());
+    assert!(usvg::ColorProfile::from_icc(&icc).is_none());
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn svg_font() {
+    use usvg::{FuzzyEq, NodeExt};
+
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <font horiz-adv-x='600'>
+            <font-face font-family='Test' units-per-em='1000' ascent='800' descent='200'/>
+            <missing-glyph d='M 0 0 L 600 0 L 600 800 Z'/>
+            <glyph unicode='A' glyph-name='a' d='M 0 0 L 500 0 L 500 700 Z'/>
+            <hkern u1='A' g2='a' k='100'/>
+        </font>
+        <text x='10' y='100' font-family='Test' font-size='100'>AA</text>
+    </svg>
+    ";
+
+    // SVG fonts do not require any system fonts.
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let bbox = tree.root().calculate_bbox().unwrap();
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 30.0, 100.0, 70.0).unwrap()));
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn svg_font_large_metrics() {
+    use usvg::{FuzzyEq, NodeExt};
+
+    // Metrics outside the `i16` range must not overflow.
+    let svg = "
+    <svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>
+        <font horiz-adv-x='600'>
+            <font-face font-family='Test' units-per-em='1000' ascent='20000' descent='40000'/>
+            <glyph unicode='A' glyph-name='a' d='M 0 0 L 500 0 L 500 700 Z'/>
+        </font>
+        <text x='10' y='100' font-family='Test' font-size='100'>A</text>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let bbox = tree.root().calculate_bbox().unwrap();
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 30.0, 50.0, 70.0).unwrap()));
+}
+
+#[cfg(feature = "text")]
+const TEST_FONT: &str = "
+    <font horiz-adv-x='600'>
+        <font-face font-family='Test' units-per-em='1000' ascent='800' descent='200'/>
+        <missing-glyph d='M 0 0 L 600 0 L 600 800 Z'/>
+        <glyph unicode='A' glyph-name='a' d='M 0 0 L 500 0 L 500 700 Z'/>
+    </font>
+";
+
+#[cfg(feature = "text")]
+fn text_bbox(font: &str, text: &str) -> usvg::Rect {
+    use usvg::NodeExt;
+
+    let svg = format!(
+        "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'>{}{}</svg>",
+        font, text
+    );
+
+    // SVG fonts do not require any system fonts.
+    let tree = usvg::Tree::from_str(&svg, &usvg::Options::default()).unwrap();
+    tree.root().calculate_bbox().unwrap()
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn text_length() {
+    use usvg::FuzzyEq;
+
+    let bbox = text_bbox(TEST_FONT, "<text x='10' y='100' font-family='Test' font-size='100' \
+                                     textLength='200'>AA</text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 30.0, 190.0, 70.0).unwrap()));
+
+    let bbox = text_bbox(TEST_FONT, "<text x='10' y='100' font-family='Test' font-size='100' \
+                                     textLength='240' lengthAdjust='spacingAndGlyphs'>AA</text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 30.0, 220.0, 70.0).unwrap()));
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn text_baseline() {
+    use usvg::FuzzyEq;
+
+    let bbox = text_bbox(TEST_FONT, "<text x='10' y='100' font-family='Test' font-size='100' \
+                                     dominant-baseline='central'>A</text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 60.0, 50.0, 70.0).unwrap()));
+
+    let bbox = text_bbox(TEST_FONT, "<text x='10' y='100' font-family='Test' font-size='100'>\
+                                     <tspan alignment-baseline='text-before-edge'>A</tspan></text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 110.0, 50.0, 70.0).unwrap()));
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn text_font_features() {
+    use usvg::FuzzyEq;
+
+    // SVG fonts do not support small caps, so they should be synthesized.
+    let bbox = text_bbox(TEST_FONT, "<text x='10' y='100' font-family='Test' font-size='100' \
+                                     font-variant='small-caps'>a</text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 51.0, 35.0, 49.0).unwrap()));
+
+    // A `kerning` length acts like `letter-spacing`.
+    let bbox = text_bbox(TEST_FONT, "<text x='10' y='100' font-family='Test' font-size='100' \
+                                     kerning='10'>AA</text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 30.0, 120.0, 70.0).unwrap()));
+
+    // Disabled kerning affects SVG fonts too.
+    let font = TEST_FONT.replace("</font>", "<hkern u1='A' g2='a' k='100'/></font>");
+    let bbox = text_bbox(&font, "<text x='10' y='100' font-family='Test' font-size='100'>AA</text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 30.0, 100.0, 70.0).unwrap()));
+
+    let bbox = text_bbox(&font, "<text x='10' y='100' font-family='Test' font-size='100' \
+                                 kerning='0'>AA</text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 30.0, 110.0, 70.0).unwrap()));
+
+    let bbox = text_bbox(&font, "<text x='10' y='100' font-family='Test' font-size='100' \
+                                 font-feature-settings='\"kern\" 0'>AA</text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 30.0, 110.0, 70.0).unwrap()));
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn text_direction() {
+    use usvg::FuzzyEq;
+
+    // `text-anchor=start` is the right edge in the RTL text.
+    let bbox = text_bbox(TEST_FONT, "<text x='190' y='100' font-family='Test' font-size='100' \
+                                     direction='rtl'>A</text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(130.0, 30.0, 50.0, 70.0).unwrap()));
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn text_wrap() {
+    use usvg::FuzzyEq;
+
+    let font = TEST_FONT.replace("</font>", "<glyph unicode=' ' glyph-name='space'/></font>");
+
+    // A trailing space hangs and the second word doesn't fit the first line.
+    let bbox = text_bbox(&font, "<text x='10' y='100' font-family='Test' font-size='100' \
+                                 inline-size='150'>AA AA</text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 30.0, 110.0, 170.0).unwrap()));
+
+    // Preserved line feeds are forced line breaks.
+    let bbox = text_bbox(&font, "<text x='10' y='100' font-family='Test' font-size='100' \
+                                 style='white-space:pre'>A\nA</text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 30.0, 50.0, 170.0).unwrap()));
+
+    let bbox = text_bbox(&font, "<defs><rect id='shape' width='200' height='250'/></defs>\
+                                 <text font-family='Test' font-size='100' \
+                                 shape-inside='url(#shape)' shape-padding='10'>AA AA</text>");
+    assert!(bbox.fuzzy_eq(&usvg::Rect::new(10.0, 20.0, 110.0, 170.0).unwrap()));
+}